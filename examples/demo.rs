@@ -1,13 +1,13 @@
-use stockfish::Stockfish;
+use stockfish::{Stockfish, StockfishError};
 
-fn main() -> Result<(), std::io::Error> {
+fn main() -> Result<(), StockfishError> {
     let path = if cfg!(target_os = "windows") {
         "./stockfish.exe"
     } else {
         "stockfish"
     };
 
-    let mut stockfish = Stockfish::new(&path)?;
+    let mut stockfish = Stockfish::new(path)?;
     stockfish.setup_for_new_game()?;
     stockfish.print_board()?;
 
@@ -1,13 +1,13 @@
-use stockfish::Stockfish;
+use stockfish::{Stockfish, StockfishError};
 
-fn main() -> Result<(), std::io::Error> {
+fn main() -> Result<(), StockfishError> {
     let path = if cfg!(target_os = "windows") {
         "./stockfish.exe"
     } else {
         "stockfish"
     };
 
-    let mut stockfish = Stockfish::new(&path)?;
+    let mut stockfish = Stockfish::new(path)?;
     stockfish.setup_for_new_game()?;
     stockfish.print_board()?;
 
@@ -18,10 +18,10 @@ fn main() -> Result<(), std::io::Error> {
 
     stockfish.set_fen_position(fen)?;
 
-    assert_eq!(fen, stockfish.get_fen()?);
+    assert_eq!(fen, stockfish.get_fen());
 
     // Testing
-    let moves = ["e2e4", "e7e5", "f1c4"];
+    let moves = ["f1c4", "f8c5", "e1g1"];
     stockfish.play_moves(&moves)?;
     //
 
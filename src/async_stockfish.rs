@@ -0,0 +1,759 @@
+use std::{io, process::Stdio, time::Duration};
+
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    process::{Child, Command},
+    sync::mpsc::{self, UnboundedReceiver, UnboundedSender},
+    time::{self, Instant},
+};
+
+use crate::engine_options::{EngineOption, EngineOptions, OptionType, OptionValue};
+use crate::engine_output::EngineOutput;
+use crate::error::{Result, StockfishError};
+use crate::go_params::GoParams;
+use crate::pgn::PgnGame;
+use crate::polyglot::{BookSelection, PolyglotBook};
+use crate::pv_line::PvLine;
+use crate::search::{SearchCollector, SearchOutcome, SearchUpdate};
+use crate::position::{Position, STARTING_FEN};
+use crate::stockfish::{color_multiplier, position_command, HANDSHAKE_TIMEOUT};
+
+/// A response that the engine still owes to an operation whose future was dropped
+/// before it completed. It is drained before the next command is sent, so that it
+/// can't be mistaken for the response to that command.
+#[derive(Debug, Clone, Copy)]
+enum Pending {
+    /// A search is running; it must be stopped, and its `bestmove` discarded.
+    Search,
+    /// The engine will output a line beginning with the given prefix.
+    Response(&'static str),
+}
+
+/// The asynchronous counterpart of [`Stockfish`](crate::Stockfish), built on
+/// tokio's child-process I/O. Requires the `async` cargo feature, and must be
+/// used within a tokio runtime.
+///
+/// All of the returned futures are cancellation-safe: if one of them is dropped
+/// before it completes (e.g., by [`tokio::time::timeout`] or `tokio::select!`),
+/// any search that it started is stopped, and any output that it was waiting for is
+/// discarded before the next command is sent to the engine.
+///
+/// # Example
+///
+/// ```no_run
+/// use stockfish::AsyncStockfish;
+/// # async fn run() -> Result<(), stockfish::StockfishError> {
+/// let mut stockfish = AsyncStockfish::new("stockfish.exe").await?;
+/// stockfish.set_depth(20);
+/// stockfish.play_moves(&["e2e4", "e7e5"]).await?;
+///
+/// let engine_output = stockfish.go().await?;
+/// println!("best move: {}", engine_output.best_move());
+/// # Ok(())
+/// # }
+/// ```
+pub struct AsyncStockfish {
+    child: Child,
+    sender: UnboundedSender<String>,
+    receiver: UnboundedReceiver<io::Result<String>>,
+    depth: u32,
+    version: Option<String>,
+    engine_name: Option<String>,
+    engine_author: Option<String>,
+    options: EngineOptions,
+    root_fen: Option<String>,
+    moves: Vec<String>,
+    position: Position,
+    last_command: String,
+    response_timeout: Option<Duration>,
+    ensure_ready_timeout: Option<Duration>,
+    pending: Option<Pending>,
+    book: Option<(PolyglotBook, BookSelection)>,
+}
+
+impl AsyncStockfish {
+
+    /// Given the path to the Stockfish binary executable, this function spawns
+    /// the engine, performs the UCI handshake, and returns an instance of the
+    /// [`AsyncStockfish`] wrapper class. The engine is killed once it is dropped,
+    /// or if it doesn't complete the handshake within 30 seconds.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`StockfishError`] if an error occurred while trying to
+    /// create/communicate with the engine, or [`StockfishError::Timeout`] if the
+    /// handshake didn't complete in time.
+    pub async fn new(path: &str) -> Result<AsyncStockfish> {
+        let mut child = Command::new(path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()?;
+
+        let stdin = child.stdin.take().ok_or_else(|| io::Error::other("engine has no stdin"))?;
+        let stdout = child.stdout.take().ok_or_else(|| io::Error::other("engine has no stdout"))?;
+
+        // Commands are written and lines are read by background tasks, so that no
+        // partial write or read is lost when one of our futures is dropped
+        let (sender, mut commands) = mpsc::unbounded_channel::<String>();
+        tokio::spawn(async move {
+            let mut stdin = stdin;
+            while let Some(command) = commands.recv().await {
+                let written = stdin.write_all(format!("{command}\n").as_bytes()).await;
+                if written.is_err() || stdin.flush().await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        let (tx, receiver) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            let mut lines = BufReader::new(stdout).lines();
+            loop {
+                match lines.next_line().await {
+                    Ok(Some(line)) => {
+                        if tx.send(Ok(line)).is_err() {
+                            break;
+                        }
+                    }
+                    Ok(None) => break,
+                    Err(err) => {
+                        let _ = tx.send(Err(err));
+                        break;
+                    }
+                }
+            }
+        });
+
+        let mut stockfish = AsyncStockfish {
+            child,
+            sender,
+            receiver,
+            depth: 15,
+            version: None,
+            engine_name: None,
+            engine_author: None,
+            options: EngineOptions::default(),
+            root_fen: None,
+            moves: Vec::new(),
+            position: Position::starting(),
+            last_command: String::new(),
+            response_timeout: None,
+            ensure_ready_timeout: None,
+            pending: None,
+            book: None,
+        };
+
+        stockfish.uci_handshake().await?;
+
+        Ok(stockfish)
+    }
+
+    /// Prepares the Stockfish process for a new game. Should be called
+    /// to indicate to the engine that the next position it will be evaluating
+    /// will be from a different game.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`StockfishError`] if an error occurred while trying to
+    /// communicate with the engine.
+    pub async fn setup_for_new_game(&mut self) -> Result<()> {
+        self.ensure_ready().await?;
+        self.uci_send("ucinewgame").await
+    }
+
+    /// Changes the current chess position in which Stockfish is currently playing.
+    /// The argument to be passed is a string in FEN (Forsyth-Edwards Notation).
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`StockfishError`] if an error occurred while trying to
+    /// communicate with the engine, or [`StockfishError::IllegalPosition`] if
+    /// the FEN is malformed.
+    pub async fn set_fen_position(&mut self, fen: &str) -> Result<()> {
+        let position = Position::from_fen(fen)?;
+        self.root_fen = Some(fen.to_owned());
+        self.moves.clear();
+        self.position = position;
+        self.send_position().await
+    }
+
+    /// Sets the current position to that of the given game after the given number of
+    /// plies of its mainline. (See [`Stockfish::set_pgn_position`](crate::Stockfish::set_pgn_position).)
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`StockfishError`] if an error occurred while trying to
+    /// communicate with the engine, or [`StockfishError::PlyOutOfRange`] if `ply` exceeds
+    /// the number of moves in the game's mainline.
+    pub async fn set_pgn_position(&mut self, game: &PgnGame, ply: usize) -> Result<()> {
+        let position = game.position_at(ply)?;
+        self.root_fen = game.root_fen().map(str::to_owned);
+        self.moves = game.uci_moves();
+        self.moves.truncate(ply);
+        self.position = position;
+        self.send_position().await
+    }
+
+    /// Reverts the current chess position to the default starting position.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`StockfishError`] if an error occurred while trying to
+    /// communicate with the engine.
+    pub async fn reset_position(&mut self) -> Result<()> {
+        self.root_fen = None;
+        self.moves.clear();
+        self.position = Position::starting();
+        self.send_position().await
+    }
+
+    /// Sends the UCI command `"isready"` to Stockfish and waits until it sends
+    /// back `"readyok"`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`StockfishError`] if an error occurred while trying to
+    /// communicate with the engine, or [`StockfishError::Timeout`] if `"readyok"`
+    /// wasn't received within the timeout set by [`AsyncStockfish::set_ensure_ready_timeout`].
+    pub async fn ensure_ready(&mut self) -> Result<()> {
+        self.request("isready", "readyok").await?;
+        let deadline = self.ensure_ready_timeout.map(|timeout| Instant::now() + timeout);
+        loop {
+            match self.read_line_until(deadline).await? {
+                Some(line) if line == "readyok" => break,
+                Some(_) => {}
+                None => {
+                    return Err(StockfishError::Timeout {
+                        command: self.last_command.clone(),
+                        timeout: self.ensure_ready_timeout.unwrap_or_default(),
+                    });
+                }
+            }
+        }
+        self.pending = None;
+        Ok(())
+    }
+
+    /// Returns the current board position in FEN notation. The position is maintained
+    /// by the wrapper itself, so the engine isn't queried.
+    #[must_use]
+    pub fn get_fen(&self) -> String {
+        self.position.to_fen()
+    }
+
+    /// Plays the given move on the current position. The move may be given in long UCI
+    /// algebraic notation (e.g., `"e2e4"`), in standard algebraic notation, or as a person
+    /// would type it; see [`Position::parse_move`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`StockfishError`] if an error occurred while trying to
+    /// communicate with the engine, [`StockfishError::AmbiguousMove`] if the move could
+    /// denote several legal moves, or [`StockfishError::IllegalMove`] if it is malformed
+    /// or illegal in the current position.
+    pub async fn play_move(&mut self, move_str: &str) -> Result<()> {
+        self.play_moves(&[move_str]).await
+    }
+
+    /// Plays the given moves, in order, on the current position. The moves are read as
+    /// with [`AsyncStockfish::play_move`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`StockfishError`] if an error occurred while trying to
+    /// communicate with the engine, or [`StockfishError::AmbiguousMove`] or
+    /// [`StockfishError::IllegalMove`] if a move doesn't denote exactly one legal move, which
+    /// names the move, its ply and the candidates or the reason it was rejected. (In that
+    /// case, none of the moves are played.)
+    pub async fn play_moves(&mut self, moves: &[&str]) -> Result<()> {
+        let mut position = self.position.clone();
+        let mut played = Vec::with_capacity(moves.len());
+        for move_str in moves {
+            let mv = position.parse_move(move_str)?;
+            position.play(mv)?;
+            played.push(mv.to_string());
+        }
+
+        self.position = position;
+        self.moves.extend(played);
+        self.send_position().await
+    }
+
+    /// Plays a move, given in standard algebraic notation (e.g., `"Nf3"` or `"O-O"`), on
+    /// the current position. (See [`Stockfish::play_san`](crate::Stockfish::play_san).)
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`StockfishError`] if an error occurred while trying to
+    /// communicate with the engine, or [`StockfishError::AmbiguousMove`] or
+    /// [`StockfishError::IllegalMove`] if the move doesn't denote exactly one legal move in
+    /// the current position.
+    pub async fn play_san(&mut self, san: &str) -> Result<()> {
+        let mv = self.position.parse_san(san)?;
+        self.play_move(&mv.to_string()).await
+    }
+
+    /// Returns the moves that have been played since the root position, in long UCI
+    /// algebraic notation. (See [`Stockfish::get_move_history`](crate::Stockfish::get_move_history).)
+    #[must_use]
+    pub fn get_move_history(&self) -> &[String] {
+        &self.moves
+    }
+
+    /// Returns the FEN of the root position, from which the moves of
+    /// [`AsyncStockfish::get_move_history`] were played.
+    #[must_use]
+    pub fn get_root_fen(&self) -> &str {
+        self.root_fen.as_deref().unwrap_or(STARTING_FEN)
+    }
+
+    /// Returns the current chess position, as tracked by the wrapper. (See [`Position`].)
+    #[must_use]
+    pub fn get_position(&self) -> &Position {
+        &self.position
+    }
+
+    /// Makes Stockfish calculate to the depth that has been set through
+    /// [`AsyncStockfish::set_depth`], and returns an [`EngineOutput`] describing the
+    /// result of its calculations.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`StockfishError`] if an error occurred while trying to
+    /// communicate with the engine.
+    pub async fn go(&mut self) -> Result<EngineOutput> {
+        self.go_with(&GoParams::new().depth(self.depth)).await
+    }
+
+    /// Makes Stockfish calculate within the limits described by the given [`GoParams`].
+    /// If `params` sets none of depth, nodes, mate, move time or clock times, the depth
+    /// that has been set through [`AsyncStockfish::set_depth`] is used instead.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use stockfish::{AsyncStockfish, GoParams};
+    /// use std::time::Duration;
+    /// # async fn run() -> Result<(), stockfish::StockfishError> {
+    /// let mut stockfish = AsyncStockfish::new("stockfish.exe").await?;
+    ///
+    /// // If the search takes too long, the future is dropped and the search is stopped
+    /// let params = GoParams::new().nodes(10_000_000);
+    /// match tokio::time::timeout(Duration::from_secs(1), stockfish.go_with(&params)).await {
+    ///     Ok(engine_output) => println!("best move: {}", engine_output?.best_move()),
+    ///     Err(_) => println!("the search took too long"),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`StockfishError`] if an error occurred while trying to
+    /// communicate with the engine, or [`StockfishError::InvalidGoParams`] if
+    /// `params` is infinite (use [`AsyncStockfish::go_for`] for such searches) or in
+    /// ponder mode, which isn't supported here.
+    pub async fn go_with(&mut self, params: &GoParams) -> Result<EngineOutput> {
+        if params.is_infinite() {
+            return Err(StockfishError::InvalidGoParams(
+                "an infinite search must be bounded through go_for".to_owned()
+            ));
+        }
+        if params.is_ponder() {
+            return Err(StockfishError::InvalidGoParams(
+                "a ponder search can't be awaited, as it only ends once stopped".to_owned()
+            ));
+        }
+        if !params.is_restricted()
+            && let Some(engine_output) = self.book_output()
+        {
+            return Ok(engine_output);
+        }
+        if !params.is_bounded() {
+            return self.search(&params.clone().depth(self.depth), None).await?.engine_output();
+        }
+        self.search(params, None).await?.engine_output()
+    }
+
+    /// Makes Stockfish calculate for the given amount of time (or until it
+    /// finishes on its own), and returns an [`EngineOutput`] describing the
+    /// result of its calculations.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`StockfishError`] if an error occurred while trying to
+    /// communicate with the engine.
+    pub async fn go_for(&mut self, calculation_time: Duration) -> Result<EngineOutput> {
+        if let Some(engine_output) = self.book_output() {
+            return Ok(engine_output);
+        }
+        let deadline = Instant::now() + calculation_time;
+        self.search(&GoParams::new().infinite(), Some(deadline)).await?.engine_output()
+    }
+
+    /// Makes Stockfish calculate based on the given clock times (in milliseconds)
+    /// of white and black.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`StockfishError`] if an error occurred while trying to
+    /// communicate with the engine.
+    pub async fn go_based_on_times(&mut self, white_time: Option<u32>, black_time: Option<u32>) -> Result<EngineOutput> {
+        let mut params = GoParams::new();
+        if let Some(time) = white_time {
+            params = params.white_time(Duration::from_millis(time.into()));
+        }
+        if let Some(time) = black_time {
+            params = params.black_time(Duration::from_millis(time.into()));
+        }
+
+        self.go_with(&params).await
+    }
+
+    /// Sets the depth to which Stockfish calculates in [`AsyncStockfish::go`].
+    pub fn set_depth(&mut self, depth: u32) {
+        self.depth = depth;
+    }
+
+    /// Sets an opening book (or removes it, given [`None`].) While the book holds moves for
+    /// the current position, [`AsyncStockfish::go`], [`AsyncStockfish::go_with`],
+    /// [`AsyncStockfish::go_for`] and [`AsyncStockfish::go_based_on_times`] return one of
+    /// them, chosen as per `selection`, without searching. (The book isn't consulted when
+    /// [`GoParams`] restrict the search to some moves, or look for a mate.)
+    pub fn set_book(&mut self, book: Option<PolyglotBook>, selection: BookSelection) {
+        self.book = book.map(|book| (book, selection));
+    }
+
+    /// Makes Stockfish calculate the given number of ranked lines to the depth
+    /// that has been set, returning them best line first.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`StockfishError`] if an error occurred while trying to
+    /// communicate with the engine.
+    pub async fn go_multipv(&mut self, lines: u32) -> Result<Vec<PvLine>> {
+        if self.get_option_value("MultiPV") != Some(&OptionValue::Spin(i64::from(lines))) {
+            self.set_option("MultiPV", &lines.to_string()).await?;
+        }
+
+        let params = GoParams::new().depth(self.depth);
+        Ok(self.search(&params, None).await?.pv_lines())
+    }
+
+    /// Returns the engine's visual representation of the current board position.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`StockfishError`] if an error occurred while trying to
+    /// communicate with the engine.
+    pub async fn get_board_display(&mut self) -> Result<String> {
+        self.request("d", "Checkers").await?;
+
+        let mut lines: Vec<String> = Vec::with_capacity(20);
+        let mut reached_fen = false;
+        loop {
+            let line = self.read_line().await?;
+            if line.starts_with("Checkers") {
+                break;
+            }
+            reached_fen |= line.starts_with("Fen:");
+            if !reached_fen && !line.is_empty() {
+                lines.push(line);
+            }
+        }
+        self.pending = None;
+        Ok(lines.join("\n"))
+    }
+
+    /// Sets the given option to the given value, after checking the value against
+    /// the option's declaration.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StockfishError::UnknownOption`] if the engine didn't declare the
+    /// option, [`StockfishError::InvalidOptionValue`] if the value isn't valid for it,
+    /// or another [`StockfishError`] if an error occurred while trying to
+    /// communicate with the engine.
+    pub async fn set_option(&mut self, option_name: &str, option_value: &str) -> Result<()> {
+        let value = self.get_declared_option(option_name)?.parse_value(option_value)?;
+        self.send_option_value(option_name, value).await
+    }
+
+    /// Triggers the action of the given button option.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StockfishError::UnknownOption`] if the engine didn't declare the
+    /// option, [`StockfishError::InvalidOptionValue`] if it isn't a button, or another
+    /// [`StockfishError`] if an error occurred while trying to communicate with the engine.
+    pub async fn press_button(&mut self, option_name: &str) -> Result<()> {
+        let option = self.get_declared_option(option_name)?;
+        if *option.option_type() != OptionType::Button {
+            return Err(StockfishError::InvalidOptionValue {
+                name: option.name().to_owned(),
+                value: String::new(),
+                reason: format!("expected a button option, but it is of type {}", option.option_type()),
+            });
+        }
+        let message = format!("setoption name {}", option.name());
+        self.uci_send(&message).await
+    }
+
+    /// Returns the value that the given option currently holds, or [`None`] if the
+    /// engine didn't declare it (or if it is a button.)
+    #[must_use]
+    pub fn get_option_value(&self, option_name: &str) -> Option<&OptionValue> {
+        self.options.get(option_name)?.value()
+    }
+
+    /// Sets the size of the engine's hashtable, in MB.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`StockfishError`] if the value is rejected or an error occurred
+    /// while trying to communicate with the engine.
+    pub async fn set_hash(&mut self, hash: u32) -> Result<()> {
+        self.set_option("Hash", &hash.to_string()).await
+    }
+
+    /// Sets the number of threads that the engine searches with.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`StockfishError`] if the value is rejected or an error occurred
+    /// while trying to communicate with the engine.
+    pub async fn set_threads(&mut self, threads: u32) -> Result<()> {
+        self.set_option("Threads", &threads.to_string()).await
+    }
+
+    /// Limits the engine's strength to the given elo.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`StockfishError`] if the value is rejected or an error occurred
+    /// while trying to communicate with the engine.
+    pub async fn set_elo(&mut self, elo: u32) -> Result<()> {
+        let elo = self.get_declared_option("UCI_Elo")?.parse_value(&elo.to_string())?;
+        self.set_option("UCI_LimitStrength", "true").await?;
+        self.send_option_value("UCI_Elo", elo).await
+    }
+
+    /// Sets the engine's skill level, removing any limit set through
+    /// [`AsyncStockfish::set_elo`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`StockfishError`] if the value is rejected or an error occurred
+    /// while trying to communicate with the engine.
+    pub async fn set_skill_level(&mut self, skill_level: u32) -> Result<()> {
+        let skill_level = self.get_declared_option("Skill Level")?.parse_value(&skill_level.to_string())?;
+        self.set_option("UCI_LimitStrength", "false").await?;
+        self.send_option_value("Skill Level", skill_level).await
+    }
+
+    /// Returns the version of the engine, as read from its `id name` line.
+    #[must_use]
+    pub fn get_version(&self) -> &Option<String> {
+        &self.version
+    }
+
+    /// Returns the name of the engine, as reported in its `id name` line.
+    #[must_use]
+    pub fn get_engine_name(&self) -> &Option<String> {
+        &self.engine_name
+    }
+
+    /// Returns the author of the engine, as reported in its `id author` line.
+    #[must_use]
+    pub fn get_engine_author(&self) -> &Option<String> {
+        &self.engine_author
+    }
+
+    /// Returns the options that the engine declared during the UCI handshake.
+    /// (Declarations that couldn't be parsed are left out.)
+    #[must_use]
+    pub fn get_options(&self) -> &EngineOptions {
+        &self.options
+    }
+
+    /// Sends the UCI command `"quit"`, and waits for the engine to exit.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`StockfishError`] if an error occurred while trying to
+    /// communicate with the engine.
+    pub async fn quit(&mut self) -> Result<()> {
+        self.uci_send("quit").await?;
+        self.child.wait().await?;
+        Ok(())
+    }
+
+    /// Sends the given command to the engine, once the output still owed to any
+    /// cancelled operation has been discarded.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`StockfishError`] if an error occurred while trying to
+    /// communicate with the engine.
+    pub async fn uci_send(&mut self, command: &str) -> Result<()> {
+        self.resync().await?;
+        self.send(command)
+    }
+
+    /// Sets the longest time to wait for any single line of output from the engine.
+    /// Set to [`None`] (the default) to wait indefinitely.
+    pub fn set_response_timeout(&mut self, timeout: Option<Duration>) {
+        self.response_timeout = timeout;
+    }
+
+    /// Sets the longest time that [`AsyncStockfish::ensure_ready`] waits for `"readyok"`.
+    /// Set to [`None`] (the default) to wait indefinitely.
+    pub fn set_ensure_ready_timeout(&mut self, timeout: Option<Duration>) {
+        self.ensure_ready_timeout = timeout;
+    }
+
+    /* Private Methods */
+
+    /// Picks a move from the opening book for the current position, if there is one.
+    fn book_output(&self) -> Option<EngineOutput> {
+        let (book, selection) = self.book.as_ref()?;
+        let book_move = book.pick_move(&self.position, *selection)?;
+        Some(EngineOutput::from_book(book_move.to_string()))
+    }
+
+    async fn send_position(&mut self) -> Result<()> {
+        let command = position_command(self.root_fen.as_deref(), &self.moves);
+        self.uci_send(&command).await
+    }
+
+    fn get_declared_option(&self, option_name: &str) -> Result<&EngineOption> {
+        self.options.get(option_name)
+            .ok_or_else(|| StockfishError::UnknownOption(option_name.to_owned()))
+    }
+
+    async fn send_option_value(&mut self, option_name: &str, value: OptionValue) -> Result<()> {
+        let message = format!("setoption name {} value {value}", self.get_declared_option(option_name)?.name());
+        self.uci_send(&message).await?;
+        if let Some(option) = self.options.get_mut(option_name) {
+            option.set_value(value);
+        }
+        Ok(())
+    }
+
+    async fn uci_handshake(&mut self) -> Result<()> {
+        self.request("uci", "uciok").await?;
+        let deadline = Instant::now() + HANDSHAKE_TIMEOUT;
+        loop {
+            let Some(line) = self.read_line_until(Some(deadline)).await? else {
+                return Err(StockfishError::Timeout { command: String::from("uci"), timeout: HANDSHAKE_TIMEOUT });
+            };
+            if line == "uciok" {
+                break;
+            }
+
+            if let Some(name) = line.strip_prefix("id name ") {
+                self.engine_name = Some(name.to_owned());
+                self.version = name.split(' ').nth(1).map(ToString::to_string);
+            } else if let Some(author) = line.strip_prefix("id author ") {
+                self.engine_author = Some(author.to_owned());
+            } else if line.starts_with("option ")
+                // An unusual declaration that can't be parsed shouldn't prevent using the engine
+                && let Ok(option) = EngineOption::parse(&line)
+            {
+                self.options.push(option);
+            }
+        }
+        self.pending = None;
+        Ok(())
+    }
+
+    async fn search(&mut self, params: &GoParams, deadline: Option<Instant>) -> Result<SearchOutcome> {
+        self.resync().await?;
+        self.pending = Some(Pending::Search);
+        self.send(&params.to_string())?;
+
+        let mut collector = SearchCollector::new(color_multiplier(self.position.side_to_move()));
+        let mut deadline = deadline;
+        loop {
+            let Some(line) = self.read_line_until(deadline).await? else {
+                // Out of time; the engine answers "stop" with its best move so far
+                self.send("stop")?;
+                deadline = None;
+                continue;
+            };
+            if let Some(SearchUpdate::BestMove(outcome)) = collector.handle_line(&line)? {
+                self.pending = None;
+                return Ok(outcome);
+            }
+        }
+    }
+
+    /// Sends a command to which the engine responds with a line beginning with
+    /// `response`, recording that response as pending until it is read.
+    async fn request(&mut self, command: &str, response: &'static str) -> Result<()> {
+        self.resync().await?;
+        self.pending = Some(Pending::Response(response));
+        self.send(command)
+    }
+
+    /// Discards the output still owed to an operation that was cancelled.
+    async fn resync(&mut self) -> Result<()> {
+        let Some(pending) = self.pending else {
+            return Ok(());
+        };
+        let response = match pending {
+            Pending::Search => {
+                self.send("stop")?;
+                "bestmove"
+            }
+            Pending::Response(response) => response,
+        };
+        while !self.read_line().await?.starts_with(response) {}
+        self.pending = None;
+        Ok(())
+    }
+
+    fn send(&mut self, command: &str) -> Result<()> {
+        self.sender.send(command.to_owned()).map_err(|_| StockfishError::EngineExited)?;
+        command.clone_into(&mut self.last_command);
+        Ok(())
+    }
+
+    async fn read_line(&mut self) -> Result<String> {
+        loop {
+            if let Some(line) = self.read_line_until(None).await? {
+                return Ok(line);
+            }
+        }
+    }
+
+    /// Reads the next line from the engine, or returns [`None`] if the deadline passes
+    /// first. (The response timeout applies regardless of the deadline.)
+    async fn read_line_until(&mut self, deadline: Option<Instant>) -> Result<Option<String>> {
+        let timeout = self.response_timeout.map(|timeout| Instant::now() + timeout);
+        let timed_out_by_deadline = match (deadline, timeout) {
+            (Some(deadline), Some(timeout)) => deadline <= timeout,
+            (deadline, _) => deadline.is_some(),
+        };
+
+        let line = match deadline.into_iter().chain(timeout).min() {
+            Some(until) => match time::timeout_at(until, self.receiver.recv()).await {
+                Ok(line) => line,
+                Err(_) if timed_out_by_deadline => return Ok(None),
+                Err(_) => {
+                    return Err(StockfishError::Timeout {
+                        command: self.last_command.clone(),
+                        timeout: self.response_timeout.unwrap_or_default(),
+                    });
+                }
+            },
+            None => self.receiver.recv().await,
+        };
+
+        match line {
+            Some(line) => Ok(Some(line?)),
+            None => Err(StockfishError::EngineExited),
+        }
+    }
+}
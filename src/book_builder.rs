@@ -0,0 +1,290 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::engine_match::centipawns;
+use crate::error::Result;
+use crate::pgn::PgnGame;
+use crate::polyglot::{self, PolyglotBook, RawEntry};
+use crate::position::{Color, Move, Position};
+use crate::stockfish::Stockfish;
+
+/// How the moves of the games added to a [`BookBuilder`] are weighted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum BookWeighting {
+    /// A move weighs 2 for every game won by the side that played it, and 1 for every
+    /// draw, so that moves that only ever lost are left out of the book.
+    #[default]
+    Results,
+    /// A move weighs 1 for every game in which it was played, whatever the result.
+    Frequency,
+}
+
+/// Builds a [`PolyglotBook`] from a collection of games, from an engine's analysis, or both.
+///
+/// Games are added through [`BookBuilder::add_game`]; their moves up to the maximum ply are
+/// weighted as per the [`BookWeighting`]. [`BookBuilder::add_analysis`] expands a tree of
+/// positions from a root instead: in each position, the engine is asked for several
+/// lines, and the moves that score within the evaluation window of the best one are added
+/// to the book (the closer to the best move, the greater the weight) and expanded in turn.
+/// Weights are scaled down where needed to fit the format.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::{fs::File, io::BufReader};
+/// use stockfish::{BookBuilder, BookWeighting, PgnReader, Position, Stockfish};
+/// # fn main() -> Result<(), stockfish::StockfishError> {
+/// let mut builder = BookBuilder::new()
+///     .max_ply(16)
+///     .min_games(3)
+///     .weighting(BookWeighting::Results);
+/// for game in PgnReader::new(BufReader::new(File::open("games.pgn")?)) {
+///     builder.add_game(&game?);
+/// }
+/// builder.build().save("games.bin")?;
+///
+/// let mut stockfish = Stockfish::new("stockfish.exe")?;
+/// stockfish.set_depth(18);
+/// let mut builder = BookBuilder::new()
+///     .max_ply(6)
+///     .analysis_lines(3)
+///     .eval_window(25);
+/// builder.add_analysis(&mut stockfish, &Position::starting())?;
+/// builder.build().save("analysis.bin")?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BookBuilder {
+    max_ply: u32,
+    min_games: u32,
+    weighting: BookWeighting,
+    analysis_lines: u32,
+    eval_window: u32,
+    /// The accumulated weight of each move, by the key of its position and its encoding.
+    moves: HashMap<(u64, u16), MoveWeight>,
+}
+
+impl BookBuilder {
+
+    /// Creates an empty builder, which adds moves up to ply 20, weighted by results, and
+    /// expands the 4 best lines of analysis within 30 centipawns of the best.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            max_ply: 20,
+            min_games: 1,
+            weighting: BookWeighting::default(),
+            analysis_lines: 4,
+            eval_window: 30,
+            moves: HashMap::new(),
+        }
+    }
+
+    /// Sets the number of plies from the start of each game (or from the root of the
+    /// analysis) for which moves are added.
+    #[must_use]
+    pub fn max_ply(mut self, plies: u32) -> Self {
+        self.max_ply = plies;
+        self
+    }
+
+    /// Leaves out the moves that were played in fewer than the given number of games.
+    /// (Moves from analysis are kept regardless.)
+    #[must_use]
+    pub fn min_games(mut self, games: u32) -> Self {
+        self.min_games = games;
+        self
+    }
+
+    /// Sets how the moves of the games are weighted.
+    #[must_use]
+    pub fn weighting(mut self, weighting: BookWeighting) -> Self {
+        self.weighting = weighting;
+        self
+    }
+
+    /// Sets the number of lines that the engine is asked for in each position of the analysis.
+    #[must_use]
+    pub fn analysis_lines(mut self, lines: u32) -> Self {
+        self.analysis_lines = lines;
+        self
+    }
+
+    /// Sets how many centipawns worse than the best move a move of the analysis may score
+    /// and still be added to the book.
+    #[must_use]
+    pub fn eval_window(mut self, window: u32) -> Self {
+        self.eval_window = window;
+        self
+    }
+
+    /// Adds the mainline moves of a game, up to the maximum ply.
+    pub fn add_game(&mut self, game: &PgnGame) {
+        let (white_points, black_points) = match game.result() {
+            "1-0" => (2, 0),
+            "0-1" => (0, 2),
+            "1/2-1/2" => (1, 1),
+            _ => (0, 0),
+        };
+
+        let mut position = game.root_position().clone();
+        for pgn_move in game.moves().iter().take(self.max_ply as usize) {
+            let points = if position.side_to_move() == Color::White {white_points} else {black_points};
+            let weight = match self.weighting {
+                BookWeighting::Results => points,
+                BookWeighting::Frequency => 1,
+            };
+            let entry = self.entry(&position, pgn_move.mv());
+            entry.games += 1;
+            entry.weight += weight;
+            position.play(pgn_move.mv()).expect("the moves of a game should be legal");
+        }
+    }
+
+    /// Expands a tree of positions from the given root with the engine's analysis, up to
+    /// the maximum ply. Each position is analyzed once (even if it is reached through
+    /// different move orders), to the depth set through [`Stockfish::set_depth`], and with
+    /// the number of lines set through [`BookBuilder::analysis_lines`]. The engine's
+    /// position is changed in the process, whereas its `"MultiPV"` option is restored
+    /// once the analysis is over.
+    ///
+    /// Since every move within the evaluation window is expanded, the number of positions
+    /// to analyze grows quickly with the maximum ply.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`StockfishError`](crate::StockfishError) if an error occurred while
+    /// communicating with the engine.
+    pub fn add_analysis(&mut self, stockfish: &mut Stockfish, root: &Position) -> Result<()> {
+        let multipv = stockfish.get_option_value("MultiPV").map(ToString::to_string);
+        let analysis = self.expand(stockfish, root);
+        let restored = match multipv {
+            Some(value) => stockfish.set_option("MultiPV", &value),
+            None => Ok(()),
+        };
+        analysis.and(restored)
+    }
+
+    /// Builds the book from the moves added so far.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use stockfish::{BookBuilder, BookWeighting, PgnReader, PolyglotBook, Position};
+    /// # fn main() -> Result<(), stockfish::StockfishError> {
+    /// let pgn = "1. e4 e5 2. Nf3 1-0\n\n1. e4 c5 0-1\n\n1. d4 d5 1/2-1/2\n\n1. e4 e5 1/2-1/2\n";
+    /// let weights = |book: &PolyglotBook, moves: &[&str]| -> Result<Vec<(String, u16)>, stockfish::StockfishError> {
+    ///     let mut position = Position::starting();
+    ///     for mv in moves {
+    ///         position.play_uci(mv)?;
+    ///     }
+    ///     Ok(book.moves(&position).iter().map(|entry| (entry.mv().to_string(), entry.weight())).collect())
+    /// };
+    ///
+    /// // 2 points per win and 1 per draw, for the side that played the move
+    /// let mut builder = BookBuilder::new().max_ply(2);
+    /// for game in PgnReader::new(pgn.as_bytes()) {
+    ///     builder.add_game(&game?);
+    /// }
+    /// let book = builder.build();
+    /// assert_eq!(book.len(), 5);
+    /// assert_eq!(weights(&book, &[])?, [("e2e4".to_owned(), 3), ("d2d4".to_owned(), 1)]);
+    /// assert_eq!(weights(&book, &["e2e4"])?, [("c7c5".to_owned(), 2), ("e7e5".to_owned(), 1)]);
+    ///
+    /// // 1 per game, leaving out the moves played in a single game
+    /// let mut builder = BookBuilder::new().max_ply(2).weighting(BookWeighting::Frequency).min_games(2);
+    /// for game in PgnReader::new(pgn.as_bytes()) {
+    ///     builder.add_game(&game?);
+    /// }
+    /// let book = builder.build();
+    /// assert_eq!(weights(&book, &[])?, [("e2e4".to_owned(), 3)]);
+    /// assert_eq!(weights(&book, &["e2e4"])?, [("e7e5".to_owned(), 2)]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn build(&self) -> PolyglotBook {
+        let mut by_position: HashMap<u64, Vec<(u16, u64)>> = HashMap::new();
+        for (&(key, mv), weight) in &self.moves {
+            let from_games = weight.games > 0;
+            if weight.weight == 0 || (from_games && weight.games < self.min_games) {
+                continue;
+            }
+            by_position.entry(key).or_default().push((mv, weight.weight));
+        }
+
+        let mut entries = Vec::new();
+        for (key, moves) in by_position {
+            // Weights are scaled down in proportion when the greatest one doesn't fit
+            let greatest = moves.iter().map(|&(_, weight)| weight).max().unwrap_or(0);
+            let divisor = greatest.div_ceil(u64::from(u16::MAX)).max(1);
+            for (mv, weight) in moves {
+                let weight = u16::try_from(weight / divisor).unwrap_or(u16::MAX).max(1);
+                entries.push(RawEntry { key, mv, weight, learn: 0 });
+            }
+        }
+        PolyglotBook::from_entries(entries)
+    }
+
+    /* Private Methods */
+
+    /// Analyzes the positions of the tree breadth-first, so that each is expanded from the
+    /// lowest ply at which it is reached.
+    fn expand(&mut self, stockfish: &mut Stockfish, root: &Position) -> Result<()> {
+        let window = i32::try_from(self.eval_window).unwrap_or(i32::MAX);
+        let mut analyzed = HashSet::new();
+        let mut pending = VecDeque::from([(root.clone(), 0)]);
+
+        while let Some((position, ply)) = pending.pop_front() {
+            if ply >= self.max_ply || !analyzed.insert(position.polyglot_key()) {
+                continue;
+            }
+            stockfish.set_fen_position(&position.to_fen())?;
+            let lines = stockfish.go_multipv(self.analysis_lines)?;
+
+            // The scores of the moves, from the point of view of the side to move
+            let side = if position.side_to_move() == Color::White {1} else {-1};
+            let scores: Vec<(Move, i32)> = lines.iter()
+                .filter_map(|line| {
+                    let mv = Move::parse(line.first_move()?).ok().filter(|&mv| position.is_legal(mv))?;
+                    Some((mv, centipawns(line.eval()) * side))
+                })
+                .collect();
+            let Some(best) = scores.iter().map(|&(_, score)| score).max() else {
+                continue;
+            };
+
+            for (mv, score) in scores {
+                let loss = best.saturating_sub(score);
+                if loss > window {
+                    continue;
+                }
+                let weight = u64::try_from(window - loss + 1).unwrap_or(1);
+                self.entry(&position, mv).weight += weight;
+
+                let mut next = position.clone();
+                next.play(mv)?;
+                pending.push_back((next, ply + 1));
+            }
+        }
+        Ok(())
+    }
+
+    fn entry(&mut self, position: &Position, mv: Move) -> &mut MoveWeight {
+        let key = (position.polyglot_key(), polyglot::encode_move(position, mv));
+        self.moves.entry(key).or_default()
+    }
+}
+
+impl Default for BookBuilder {
+    fn default() -> Self {
+        BookBuilder::new()
+    }
+}
+
+/// The weight accumulated by a move, and the number of games in which it was played.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+struct MoveWeight {
+    weight: u64,
+    games: u32,
+}
@@ -0,0 +1,27 @@
+/// The quantile of the standard normal distribution for a two-sided 95% confidence interval.
+const Z_95: f64 = 1.959_963_984_540_054;
+
+/// Converts an expected score (between 0 and 1) into an Elo difference.
+pub(crate) fn elo_from_score(score: f64) -> f64 {
+    400.0 * (score / (1.0 - score)).log10()
+}
+
+/// Returns the half-width of the 95% confidence interval of the Elo difference, given the
+/// number of wins, draws and losses. (The interval is infinite if it reaches a score of 0 or 1.)
+pub(crate) fn elo_error(wins: u32, draws: u32, losses: u32) -> f64 {
+    let games = f64::from(wins + draws + losses);
+    if games == 0.0 {
+        return f64::INFINITY;
+    }
+    let score = (f64::from(wins) + f64::from(draws) / 2.0) / games;
+    let variance = (f64::from(wins) * (1.0 - score).powi(2)
+        + f64::from(draws) * (0.5 - score).powi(2)
+        + f64::from(losses) * score.powi(2)) / games;
+    let deviation = Z_95 * (variance / games).sqrt();
+
+    let (low, high) = (score - deviation, score + deviation);
+    if low <= 0.0 || high >= 1.0 {
+        return f64::INFINITY;
+    }
+    (elo_from_score(high) - elo_from_score(low)) / 2.0
+}
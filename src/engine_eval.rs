@@ -1,5 +1,7 @@
 use std::fmt;
 
+use crate::error::StockfishError;
+
 /// The category of evaluation returned by stockfish. Either `Centipawns` or `Mate`.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum EvalType {Centipawn, Mate}
@@ -10,15 +12,15 @@ impl EvalType {
     /// - `"cp"`, which translates to [`EvalType::Centipawn`]
     /// - `"mate"`, which translates to [`EvalType::Mate`]
     /// 
-    /// # Panics
+    /// # Errors
     /// 
-    /// This function panics when given a string descriptor that doesn't match those listed above.
-    #[must_use]
-    pub fn from_descriptor(str: &str) -> EvalType {
+    /// Returns [`StockfishError::UnknownEvalDescriptor`] when given a string descriptor
+    /// that doesn't match those listed above.
+    pub fn from_descriptor(str: &str) -> Result<EvalType, StockfishError> {
         match str {
-            "cp" => EvalType::Centipawn,
-            "mate" => EvalType::Mate,
-            _ => panic!("Unable to create eval type")
+            "cp" => Ok(EvalType::Centipawn),
+            "mate" => Ok(EvalType::Mate),
+            _ => Err(StockfishError::UnknownEvalDescriptor(str.to_owned()))
         }
     }
 }
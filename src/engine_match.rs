@@ -0,0 +1,824 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    time::{Duration, Instant},
+};
+
+use crate::elo;
+use crate::engine_eval::{EngineEval, EvalType};
+use crate::engine_pool::EnginePool;
+use crate::error::Result;
+use crate::go_params::GoParams;
+use crate::pgn::PgnGame;
+use crate::position::{Color, Move, Position};
+use crate::sprt::{Sprt, SprtStatus};
+use crate::stockfish::Stockfish;
+
+/// A named engine configuration: the binary executable to run, and the options to set on it.
+///
+/// # Example
+///
+/// ```no_run
+/// use stockfish::EngineConfig;
+/// # fn main() -> Result<(), stockfish::StockfishError> {
+/// let config = EngineConfig::new("sf-1t", "stockfish.exe")
+///     .option("Threads", "1")
+///     .option("Hash", "64");
+/// let stockfish = config.spawn()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EngineConfig {
+    name: String,
+    path: String,
+    options: Vec<(String, String)>,
+}
+
+impl EngineConfig {
+
+    /// Creates a configuration with the given name (as used in PGN headers and reports)
+    /// that runs the binary executable at the given path, without setting any options.
+    #[must_use]
+    pub fn new(name: &str, path: &str) -> Self {
+        Self { name: name.to_owned(), path: path.to_owned(), options: Vec::new() }
+    }
+
+    /// Adds an option to set once the engine is spawned. Options are set in the order in
+    /// which they are added.
+    #[must_use]
+    pub fn option(mut self, name: &str, value: &str) -> Self {
+        self.options.push((name.to_owned(), value.to_owned()));
+        self
+    }
+
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[must_use]
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    #[must_use]
+    pub fn options(&self) -> &[(String, String)] {
+        &self.options
+    }
+
+    /// Spawns an engine, and sets the options of the configuration on it.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`StockfishError`](crate::StockfishError) if the engine couldn't be created,
+    /// or if one of the options was rejected.
+    pub fn spawn(&self) -> Result<Stockfish> {
+        let mut stockfish = Stockfish::new(&self.path)?;
+        for (name, value) in &self.options {
+            stockfish.set_option(name, value)?;
+        }
+        Ok(stockfish)
+    }
+
+    /// Spawns a pool of `size` engines, each with the options of the configuration set on it.
+    pub(crate) fn spawn_pool(&self, size: usize) -> Result<EnginePool> {
+        let options = self.options.clone();
+        EnginePool::with_setup(&self.path, size, move |stockfish| {
+            for (name, value) in &options {
+                stockfish.set_option(name, value)?;
+            }
+            Ok(())
+        })
+    }
+}
+
+/// The time that each side gets for a game: a base time, plus an increment after each move.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeControl {
+    base: Duration,
+    increment: Duration,
+}
+
+impl TimeControl {
+
+    #[must_use]
+    pub fn new(base: Duration, increment: Duration) -> Self {
+        Self { base, increment }
+    }
+
+    #[must_use]
+    pub fn base(&self) -> Duration {
+        self.base
+    }
+
+    #[must_use]
+    pub fn increment(&self) -> Duration {
+        self.increment
+    }
+}
+
+impl Default for TimeControl {
+    /// Ten seconds per game, plus a tenth of a second per move.
+    fn default() -> Self {
+        TimeControl::new(Duration::from_secs(10), Duration::from_millis(100))
+    }
+}
+
+impl fmt::Display for TimeControl {
+    /// Formats the time control as in the `[TimeControl]` tag of PGN (e.g., `"10+0.1"`.)
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}+{}", self.base.as_secs_f64(), self.increment.as_secs_f64())
+    }
+}
+
+/// The rules by which games are decided before they are over on the board.
+/// By default, no game is adjudicated.
+///
+/// # Example
+///
+/// ```rust
+/// use stockfish::Adjudication;
+///
+/// let adjudication = Adjudication::new()
+///     .resign(600, 3)
+///     .draw(40, 10, 8)
+///     .max_plies(400);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Adjudication {
+    resign: Option<(i32, u32)>,
+    draw: Option<(u32, i32, u32)>,
+    max_plies: Option<u32>,
+}
+
+impl Adjudication {
+
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adjudicates a game as lost by an engine once its own evaluation has been at least
+    /// `score` centipawns in its opponent's favor for `moves` of its consecutive moves.
+    #[must_use]
+    pub fn resign(mut self, score: u32, moves: u32) -> Self {
+        self.resign = Some((i32::try_from(score).unwrap_or(i32::MAX), moves));
+        self
+    }
+
+    /// Adjudicates a game as drawn once, from move `after_move` on, the evaluations of both
+    /// engines have been within `score` centipawns of zero for `moves` consecutive moves each.
+    #[must_use]
+    pub fn draw(mut self, after_move: u32, score: u32, moves: u32) -> Self {
+        self.draw = Some((after_move, i32::try_from(score).unwrap_or(i32::MAX), moves));
+        self
+    }
+
+    /// Adjudicates a game as drawn once it has lasted the given number of plies
+    /// (including those of the opening.)
+    #[must_use]
+    pub fn max_plies(mut self, plies: u32) -> Self {
+        self.max_plies = Some(plies);
+        self
+    }
+}
+
+/// The result of a game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GameResult {
+    WhiteWins,
+    BlackWins,
+    Draw,
+}
+
+impl GameResult {
+    /// Returns the score of the given side: 1 for a win, 0.5 for a draw and 0 for a loss.
+    #[must_use]
+    pub fn score(self, color: Color) -> f64 {
+        match (self, color) {
+            (GameResult::Draw, _) => 0.5,
+            (GameResult::WhiteWins, Color::White) | (GameResult::BlackWins, Color::Black) => 1.0,
+            _ => 0.0,
+        }
+    }
+
+    /// The result of a game won by the given side.
+    fn win(color: Color) -> GameResult {
+        match color {
+            Color::White => GameResult::WhiteWins,
+            Color::Black => GameResult::BlackWins,
+        }
+    }
+}
+
+impl fmt::Display for GameResult {
+    /// Formats the result as in PGN (e.g., `"1-0"`.)
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", match self {
+            GameResult::WhiteWins => "1-0",
+            GameResult::BlackWins => "0-1",
+            GameResult::Draw => "1/2-1/2",
+        })
+    }
+}
+
+/// The reason for which a game ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Termination {
+    Checkmate,
+    Stalemate,
+    ThreefoldRepetition,
+    FiftyMoveRule,
+    InsufficientMaterial,
+    /// An engine ran out of time.
+    TimeForfeit,
+    /// An engine played an illegal move, or none at all.
+    IllegalMove,
+    /// An engine was found to be lost, as per [`Adjudication::resign`].
+    ResignAdjudication,
+    /// The game was found to be drawn, as per [`Adjudication::draw`].
+    DrawAdjudication,
+    /// The game reached the length given by [`Adjudication::max_plies`].
+    MaxPlies,
+}
+
+impl fmt::Display for Termination {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", match self {
+            Termination::Checkmate => "checkmate",
+            Termination::Stalemate => "stalemate",
+            Termination::ThreefoldRepetition => "threefold repetition",
+            Termination::FiftyMoveRule => "fifty-move rule",
+            Termination::InsufficientMaterial => "insufficient material",
+            Termination::TimeForfeit => "time forfeit",
+            Termination::IllegalMove => "illegal move",
+            Termination::ResignAdjudication => "adjudicated as lost",
+            Termination::DrawAdjudication => "adjudicated as drawn",
+            Termination::MaxPlies => "maximum length reached",
+        })
+    }
+}
+
+impl Termination {
+    const ALL: [Termination; 10] = [
+        Termination::Checkmate,
+        Termination::Stalemate,
+        Termination::ThreefoldRepetition,
+        Termination::FiftyMoveRule,
+        Termination::InsufficientMaterial,
+        Termination::TimeForfeit,
+        Termination::IllegalMove,
+        Termination::ResignAdjudication,
+        Termination::DrawAdjudication,
+        Termination::MaxPlies,
+    ];
+
+    /// Reads a termination back from its description, as formatted by [`fmt::Display`].
+    pub(crate) fn from_description(description: &str) -> Option<Termination> {
+        Termination::ALL.into_iter().find(|termination| termination.to_string() == description)
+    }
+}
+
+/// A game played between two engines, along with how it ended.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameRecord {
+    pub(crate) white: usize,
+    pub(crate) black: usize,
+    pub(crate) opening: usize,
+    pub(crate) game: PgnGame,
+    pub(crate) result: GameResult,
+    pub(crate) termination: Termination,
+}
+
+impl GameRecord {
+
+    /// Creates the record of a game played between the engines with the given indices,
+    /// e.g. to tally games that were played elsewhere through [`MatchResult::from_games`].
+    #[must_use]
+    pub fn new(
+        white: usize,
+        black: usize,
+        opening: usize,
+        game: PgnGame,
+        result: GameResult,
+        termination: Termination,
+    ) -> Self {
+        Self { white, black, opening, game, result, termination }
+    }
+
+    /// Returns the index of the engine that played white (e.g., 0 for the first engine of
+    /// an [`EngineMatch`].)
+    #[must_use]
+    pub fn white(&self) -> usize {
+        self.white
+    }
+
+    /// Returns the index of the engine that played black.
+    #[must_use]
+    pub fn black(&self) -> usize {
+        self.black
+    }
+
+    /// Returns the index of the opening from which the game started.
+    #[must_use]
+    pub fn opening(&self) -> usize {
+        self.opening
+    }
+
+    /// Returns the game, including its opening moves, with each engine move annotated with
+    /// the engine's evaluation and the time left on its clock.
+    #[must_use]
+    pub fn game(&self) -> &PgnGame {
+        &self.game
+    }
+
+    #[must_use]
+    pub fn result(&self) -> GameResult {
+        self.result
+    }
+
+    #[must_use]
+    pub fn termination(&self) -> Termination {
+        self.termination
+    }
+
+    /// Returns the score of the engine with the given index in this game.
+    #[must_use]
+    pub fn score_of(&self, engine: usize) -> f64 {
+        let color = if engine == self.white {Color::White} else {Color::Black};
+        self.result.score(color)
+    }
+}
+
+/// A match between two engine configurations: a number of games played with a time
+/// control, in which the engines alternate colors. Each opening of the opening set is
+/// played twice in a row, once with either engine as white.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use stockfish::{Adjudication, EngineConfig, EngineMatch, PgnGame, TimeControl};
+/// # fn main() -> Result<(), stockfish::StockfishError> {
+/// let base = EngineConfig::new("base", "stockfish.exe");
+/// let weakened = EngineConfig::new("skill-10", "stockfish.exe").option("Skill Level", "10");
+///
+/// let openings = PgnGame::parse_all("1. e4 e5 *\n1. d4 d5 *\n1. c4 e5 *")?;
+/// let engine_match = EngineMatch::new(base, weakened, 100)
+///     .time_control(TimeControl::new(Duration::from_secs(5), Duration::from_millis(50)))
+///     .openings(openings)
+///     .adjudication(Adjudication::new().resign(800, 3).max_plies(300));
+///
+/// let result = engine_match.run_with(|result| {
+///     println!("{result}");
+/// })?;
+/// println!("Elo difference: {:+.1} ± {:.1}", result.elo_difference(), result.elo_error());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct EngineMatch {
+    engines: [EngineConfig; 2],
+    games: u32,
+    time_control: TimeControl,
+    openings: Vec<PgnGame>,
+    adjudication: Adjudication,
+    sprt: Option<Sprt>,
+}
+
+impl EngineMatch {
+
+    /// Creates a match of the given number of games between two engine configurations,
+    /// with the default [`TimeControl`], from the starting position, and without adjudication.
+    #[must_use]
+    pub fn new(first: EngineConfig, second: EngineConfig, games: u32) -> Self {
+        Self {
+            engines: [first, second],
+            games,
+            time_control: TimeControl::default(),
+            openings: Vec::new(),
+            adjudication: Adjudication::default(),
+            sprt: None,
+        }
+    }
+
+    #[must_use]
+    pub fn time_control(mut self, time_control: TimeControl) -> Self {
+        self.time_control = time_control;
+        self
+    }
+
+    /// Sets the openings from which the games start, each of which is played from its root
+    /// position through its mainline. (Their order is kept; shuffle them beforehand to vary it.)
+    #[must_use]
+    pub fn openings(mut self, openings: Vec<PgnGame>) -> Self {
+        self.openings = openings;
+        self
+    }
+
+    #[must_use]
+    pub fn adjudication(mut self, adjudication: Adjudication) -> Self {
+        self.adjudication = adjudication;
+        self
+    }
+
+    /// Runs a sequential probability ratio test alongside the match, which then stops as
+    /// soon as the test accepts either hypothesis after a game pair. The number of games
+    /// given to [`EngineMatch::new`] becomes the most that may be played.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stockfish::{EngineConfig, EngineMatch, Sprt, SprtStatus};
+    /// # fn main() -> Result<(), stockfish::StockfishError> {
+    /// let patched = EngineConfig::new("patch", "stockfish-patch.exe");
+    /// let base = EngineConfig::new("base", "stockfish.exe");
+    ///
+    /// let result = EngineMatch::new(patched, base, 20_000)
+    ///     .sprt(Sprt::new(0.0, 2.0, 0.05, 0.05))
+    ///     .run()?;
+    /// if result.sprt_status() == Some(SprtStatus::AcceptH1) {
+    ///     println!("The patch gains Elo (LLR {:.2})", result.llr().unwrap_or_default());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn sprt(mut self, sprt: Sprt) -> Self {
+        self.sprt = Some(sprt);
+        self
+    }
+
+    /// Plays every game of the match (or until its SPRT concludes), and returns the results.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`StockfishError`](crate::StockfishError) if an engine couldn't be created,
+    /// or if an error occurred while communicating with one.
+    pub fn run(&self) -> Result<MatchResult> {
+        self.run_with(|_| {})
+    }
+
+    /// Plays every game of the match (or until its SPRT concludes), calling the given
+    /// function with the results so far after each game.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`StockfishError`](crate::StockfishError) if an engine couldn't be created,
+    /// or if an error occurred while communicating with one.
+    pub fn run_with<F: FnMut(&MatchResult)>(&self, mut on_game: F) -> Result<MatchResult> {
+        let mut engines = [self.engines[0].spawn()?, self.engines[1].spawn()?];
+        let mut result = MatchResult::new(self.engines[0].name(), self.engines[1].name(), self.sprt);
+
+        for index in 0..self.games as usize {
+            let record = self.play_game(&mut engines, index)?;
+            result.record(record);
+            on_game(&result);
+            if result.sprt_status().is_some_and(|status| status != SprtStatus::Continue) {
+                break;
+            }
+        }
+
+        for engine in &mut engines {
+            engine.quit()?;
+        }
+        Ok(result)
+    }
+
+    /* Private Methods */
+
+    /// Plays the game with the given index, in which the engines alternate colors and
+    /// openings change every other game.
+    fn play_game(&self, engines: &mut [Stockfish; 2], index: usize) -> Result<GameRecord> {
+        let opening_index = if self.openings.is_empty() {0} else {index / 2 % self.openings.len()};
+        let starting_position = PgnGame::new();
+        let opening = self.openings.get(opening_index).unwrap_or(&starting_position);
+
+        let (white, black) = if index.is_multiple_of(2) {(0, 1)} else {(1, 0)};
+        let [first, second] = engines;
+        let (white_engine, black_engine) = if white == 0 {(first, second)} else {(second, first)};
+
+        let (mut game, result, termination) =
+            play_game(white_engine, black_engine, opening, self.time_control, self.adjudication)?;
+        game.set_header("Event", &format!("{} vs {}", self.engines[0].name(), self.engines[1].name()));
+        game.set_header("Round", &(index + 1).to_string());
+        game.set_header("White", self.engines[white].name());
+        game.set_header("Black", self.engines[black].name());
+
+        Ok(GameRecord { white, black, opening: opening_index, game, result, termination })
+    }
+}
+
+/// The results of an [`EngineMatch`], from the point of view of its first engine.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatchResult {
+    names: [String; 2],
+    wins: u32,
+    draws: u32,
+    losses: u32,
+    pentanomial: [u32; 5],
+    games: Vec<GameRecord>,
+    sprt: Option<Sprt>,
+}
+
+impl MatchResult {
+
+    /// Tallies the games of a match between two engines, with indices 0 and 1, in the order
+    /// in which they were played. Consecutive games make up the pairs of the pentanomial
+    /// counts, as in an [`EngineMatch`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use stockfish::{GameRecord, GameResult, MatchResult, PgnGame, Termination};
+    ///
+    /// // The first engine plays white in the first game of each pair
+    /// let results = [
+    ///     GameResult::WhiteWins, GameResult::BlackWins, // 2 points out of 2
+    ///     GameResult::Draw, GameResult::WhiteWins,      // 0.5 points
+    ///     GameResult::WhiteWins, GameResult::Draw,      // 1.5 points
+    /// ];
+    /// let games = results.into_iter().enumerate().map(|(index, result)| {
+    ///     let (white, black) = if index % 2 == 0 {(0, 1)} else {(1, 0)};
+    ///     let termination = match result {
+    ///         GameResult::Draw => Termination::DrawAdjudication,
+    ///         _ => Termination::ResignAdjudication,
+    ///     };
+    ///     GameRecord::new(white, black, index / 2, PgnGame::new(), result, termination)
+    /// });
+    ///
+    /// let result = MatchResult::from_games("new", "old", games);
+    /// assert_eq!((result.wins(), result.draws(), result.losses()), (3, 2, 1));
+    /// assert_eq!(result.pentanomial(), [0, 1, 0, 1, 1]);
+    /// assert_eq!(format!("{:.1} ± {:.1}", result.elo_difference(), result.elo_error()), "120.4 ± 334.5");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if a game wasn't played between the engines 0 and 1.
+    #[must_use]
+    pub fn from_games(first: &str, second: &str, games: impl IntoIterator<Item = GameRecord>) -> MatchResult {
+        let mut result = MatchResult::new(first, second, None);
+        for game in games {
+            assert!(
+                matches!((game.white, game.black), (0, 1) | (1, 0)),
+                "a game of a match should be played between the engines 0 and 1",
+            );
+            result.record(game);
+        }
+        result
+    }
+
+    /// Returns the number of games won by the first engine.
+    #[must_use]
+    pub fn wins(&self) -> u32 {
+        self.wins
+    }
+
+    #[must_use]
+    pub fn draws(&self) -> u32 {
+        self.draws
+    }
+
+    /// Returns the number of games lost by the first engine.
+    #[must_use]
+    pub fn losses(&self) -> u32 {
+        self.losses
+    }
+
+    /// Returns the games played so far, in order.
+    #[must_use]
+    pub fn games(&self) -> &[GameRecord] {
+        &self.games
+    }
+
+    /// Returns the score of the first engine as a fraction of the games played
+    /// (0.5 if no game has been played.)
+    #[must_use]
+    pub fn score(&self) -> f64 {
+        let games = self.wins + self.draws + self.losses;
+        if games == 0 {
+            return 0.5;
+        }
+        (f64::from(self.wins) + f64::from(self.draws) / 2.0) / f64::from(games)
+    }
+
+    /// Returns the estimated Elo difference of the first engine over the second
+    /// (infinite if either engine has scored every point.)
+    #[must_use]
+    pub fn elo_difference(&self) -> f64 {
+        elo::elo_from_score(self.score())
+    }
+
+    /// Returns the half-width of the 95% confidence interval of the Elo difference, so
+    /// that the true difference lies within `elo_difference() ± elo_error()`.
+    #[must_use]
+    pub fn elo_error(&self) -> f64 {
+        elo::elo_error(self.wins, self.draws, self.losses)
+    }
+
+    /// Returns the numbers of completed game pairs (two games from the same opening) in
+    /// which the first engine scored 0, 0.5, 1, 1.5 and 2 points.
+    #[must_use]
+    pub fn pentanomial(&self) -> [u32; 5] {
+        self.pentanomial
+    }
+
+    #[must_use]
+    pub fn sprt(&self) -> Option<Sprt> {
+        self.sprt
+    }
+
+    /// Returns the log-likelihood ratio of the match's SPRT over the completed game pairs,
+    /// or `None` if the match has no SPRT.
+    #[must_use]
+    pub fn llr(&self) -> Option<f64> {
+        self.sprt.map(|sprt| sprt.llr(&self.pentanomial))
+    }
+
+    /// Returns the outcome of the match's SPRT so far, or `None` if the match has no SPRT.
+    #[must_use]
+    pub fn sprt_status(&self) -> Option<SprtStatus> {
+        self.sprt.map(|sprt| sprt.status(&self.pentanomial))
+    }
+
+    /* Private Methods */
+
+    fn new(first: &str, second: &str, sprt: Option<Sprt>) -> MatchResult {
+        MatchResult {
+            names: [first.to_owned(), second.to_owned()],
+            wins: 0,
+            draws: 0,
+            losses: 0,
+            pentanomial: [0; 5],
+            games: Vec::new(),
+            sprt,
+        }
+    }
+
+    fn record(&mut self, record: GameRecord) {
+        match (record.result, record.white == 0) {
+            (GameResult::Draw, _) => self.draws += 1,
+            (GameResult::WhiteWins, true) | (GameResult::BlackWins, false) => self.wins += 1,
+            _ => self.losses += 1,
+        }
+        self.games.push(record);
+
+        if let [.., first_game, second_game] = self.games.as_slice()
+            && self.games.len().is_multiple_of(2)
+        {
+            // Half-points scored by the first engine over the pair
+            let half_points = 2.0 * (first_game.score_of(0) + second_game.score_of(0));
+            self.pentanomial[half_points as usize] += 1;
+        }
+    }
+}
+
+impl fmt::Display for MatchResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} vs {}: {} - {} - {} (W/D/L), Elo difference: {:+.1} ± {:.1}",
+            self.names[0], self.names[1], self.wins, self.draws, self.losses,
+            self.elo_difference(), self.elo_error(),
+        )?;
+        if let (Some(sprt), Some(llr), Some(status)) = (self.sprt, self.llr(), self.sprt_status()) {
+            write!(f, ", LLR: {llr:.2} ({:.2}, {:.2})", sprt.lower_bound(), sprt.upper_bound())?;
+            if status != SprtStatus::Continue {
+                write!(f, ", {status}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A score standing in for a forced mate, for the purpose of adjudication.
+const MATE_SCORE: i32 = 100_000;
+
+/// Plays a game between two engines from the end of the given opening, and returns it
+/// along with its result and the reason it ended.
+pub(crate) fn play_game(
+    white: &mut Stockfish,
+    black: &mut Stockfish,
+    opening: &PgnGame,
+    time_control: TimeControl,
+    adjudication: Adjudication,
+) -> Result<(PgnGame, GameResult, Termination)> {
+    let opening_plies = opening.moves().len();
+    let mut game = PgnGame::from_moves(opening.root_fen(), &opening.uci_moves())?;
+    game.set_header("TimeControl", &time_control.to_string());
+    let mut position = opening.position_at(opening_plies)?;
+    for engine in [&mut *white, &mut *black] {
+        engine.setup_for_new_game()?;
+        engine.set_pgn_position(opening, opening_plies)?;
+    }
+
+    let mut clocks = [time_control.base; 2];
+    let mut repetitions: HashMap<String, u32> = HashMap::new();
+    *repetitions.entry(repetition_key(&position)).or_default() += 1;
+    // The number of consecutive moves of each side that count towards an adjudication
+    let mut losing_moves = [0; 2];
+    let mut drawn_moves = 0;
+    let mut plies = u32::try_from(opening_plies).unwrap_or(u32::MAX);
+
+    let (result, termination) = loop {
+        if let Some(outcome) = rules_outcome(&position, &repetitions) {
+            break outcome;
+        }
+        if adjudication.max_plies.is_some_and(|max_plies| plies >= max_plies) {
+            break (GameResult::Draw, Termination::MaxPlies);
+        }
+
+        let side = position.side_to_move();
+        let side_index = side as usize;
+        let engine = if side == Color::White {&mut *white} else {&mut *black};
+        let params = GoParams::new()
+            .white_time(clocks[0])
+            .black_time(clocks[1])
+            .white_increment(time_control.increment)
+            .black_increment(time_control.increment);
+
+        let start = Instant::now();
+        let output = engine.go_with(&params)?;
+        let elapsed = start.elapsed();
+        if elapsed > clocks[side_index] {
+            break (GameResult::win(side.opposite()), Termination::TimeForfeit);
+        }
+        clocks[side_index] = clocks[side_index] - elapsed + time_control.increment;
+
+        let mv = Move::parse(output.best_move()).ok().filter(|&mv| position.is_legal(mv));
+        let Some(mv) = mv else {
+            break (GameResult::win(side.opposite()), Termination::IllegalMove);
+        };
+        let pgn_move = game.push_move(output.best_move())?;
+        pgn_move.set_eval(Some(output.eval()));
+        pgn_move.set_clock(Some(clocks[side_index]));
+        position.play(mv)?;
+        plies += 1;
+        *repetitions.entry(repetition_key(&position)).or_default() += 1;
+        for engine in [&mut *white, &mut *black] {
+            engine.play_move(output.best_move())?;
+        }
+
+        // The evaluation from the point of view of the side that moved
+        let score = centipawns(output.eval()) * if side == Color::White {1} else {-1};
+        if let Some((resign_score, moves)) = adjudication.resign {
+            losing_moves[side_index] = if score <= -resign_score {losing_moves[side_index] + 1} else {0};
+            if losing_moves[side_index] >= moves {
+                break (GameResult::win(side.opposite()), Termination::ResignAdjudication);
+            }
+        }
+        if let Some((after_move, draw_score, moves)) = adjudication.draw {
+            drawn_moves = if score.abs() <= draw_score {drawn_moves + 1} else {0};
+            if position.fullmove_number() > after_move && drawn_moves >= moves * 2 {
+                break (GameResult::Draw, Termination::DrawAdjudication);
+            }
+        }
+    };
+
+    game.set_result(&result.to_string());
+    game.set_header("Termination", match termination {
+        Termination::TimeForfeit => "time forfeit",
+        Termination::IllegalMove => "rules infraction",
+        Termination::ResignAdjudication | Termination::DrawAdjudication | Termination::MaxPlies => "adjudication",
+        _ => "normal",
+    });
+    Ok((game, result, termination))
+}
+
+/// Returns the result of the game if the position ends it by the rules of chess.
+fn rules_outcome(position: &Position, repetitions: &HashMap<String, u32>) -> Option<(GameResult, Termination)> {
+    if position.legal_moves().is_empty() {
+        return Some(if position.is_check() {
+            (GameResult::win(position.side_to_move().opposite()), Termination::Checkmate)
+        } else {
+            (GameResult::Draw, Termination::Stalemate)
+        });
+    }
+    if position.is_insufficient_material() {
+        return Some((GameResult::Draw, Termination::InsufficientMaterial));
+    }
+    if position.halfmove_clock() >= 100 {
+        return Some((GameResult::Draw, Termination::FiftyMoveRule));
+    }
+    if repetitions.get(&repetition_key(position)).is_some_and(|&count| count >= 3) {
+        return Some((GameResult::Draw, Termination::ThreefoldRepetition));
+    }
+    None
+}
+
+/// Identifies a position for the purpose of detecting repetitions: the FEN without its
+/// move counters.
+fn repetition_key(position: &Position) -> String {
+    position.to_fen().split(' ').take(4).collect::<Vec<_>>().join(" ")
+}
+
+/// Converts an evaluation into centipawns, counting forced mates as a very large score.
+pub(crate) fn centipawns(eval: EngineEval) -> i32 {
+    match eval.eval_type() {
+        EvalType::Centipawn => eval.value(),
+        EvalType::Mate if eval.value() >= 0 => MATE_SCORE,
+        EvalType::Mate => -MATE_SCORE,
+    }
+}
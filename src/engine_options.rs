@@ -0,0 +1,347 @@
+use std::fmt;
+
+use crate::error::{Result, StockfishError};
+
+/// The type of a UCI option, along with its default value and any constraints
+/// that the engine declared for it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum OptionType {
+    /// A boolean option (`type check`).
+    Check { default: bool },
+    /// An integer option within an inclusive range (`type spin`).
+    Spin { default: i64, min: i64, max: i64 },
+    /// An option whose value is one of a predefined set of strings (`type combo`).
+    Combo { default: String, vars: Vec<String> },
+    /// An option without a value, which triggers an action when set (`type button`).
+    Button,
+    /// A free-form text option (`type string`).
+    String { default: String },
+}
+
+impl OptionType {
+
+    /// Returns the default value declared for the option, or [`None`] for button options.
+    #[must_use]
+    pub fn default_value(&self) -> Option<OptionValue> {
+        match self {
+            OptionType::Check { default } => Some(OptionValue::Check(*default)),
+            OptionType::Spin { default, .. } => Some(OptionValue::Spin(*default)),
+            OptionType::Combo { default, .. } => Some(OptionValue::Combo(default.clone())),
+            OptionType::Button => None,
+            OptionType::String { default } => Some(OptionValue::String(default.clone())),
+        }
+    }
+}
+impl fmt::Display for OptionType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", match self {
+            OptionType::Check { .. } => "check",
+            OptionType::Spin { .. } => "spin",
+            OptionType::Combo { .. } => "combo",
+            OptionType::Button => "button",
+            OptionType::String { .. } => "string",
+        })
+    }
+}
+
+/// A value held by a UCI option. (Button options hold no value.)
+#[derive(Debug, Clone, PartialEq)]
+pub enum OptionValue {
+    Check(bool),
+    Spin(i64),
+    Combo(String),
+    String(String),
+}
+
+impl fmt::Display for OptionValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OptionValue::Check(value) => write!(f, "{value}"),
+            OptionValue::Spin(value) => write!(f, "{value}"),
+            OptionValue::Combo(value) | OptionValue::String(value) => write!(f, "{value}"),
+        }
+    }
+}
+
+/// Represents one option that the engine declared during the UCI handshake, e.g.
+/// `option name Hash type spin default 16 min 1 max 33554432`, along with the
+/// value that it currently holds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EngineOption {
+    name: String,
+    option_type: OptionType,
+    value: Option<OptionValue>,
+}
+
+impl EngineOption {
+
+    #[must_use]
+    pub fn new(name: String, option_type: OptionType) -> Self {
+        let value = option_type.default_value();
+        Self { name, option_type, value }
+    }
+
+    /// Parses an `option` line outputted by the engine in response to the `"uci"` command.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use stockfish::{EngineOption, OptionType};
+    /// # fn main() -> Result<(), stockfish::StockfishError> {
+    /// let option = EngineOption::parse("option name Skill Level type spin default 20 min 0 max 20")?;
+    /// assert_eq!(option.name(), "Skill Level");
+    /// assert_eq!(option.option_type(), &OptionType::Spin { default: 20, min: 0, max: 20 });
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StockfishError::MalformedOutput`] if the line isn't a well-formed
+    /// option declaration.
+    pub fn parse(line: &str) -> Result<EngineOption> {
+        let mut segments = line.split(' ');
+        if segments.next() != Some("option") {
+            return Err(StockfishError::malformed(line, "option line should begin with \"option\""));
+        }
+
+        let mut name: Option<String> = None;
+        let mut type_descriptor: Option<String> = None;
+        let mut default: Option<String> = None;
+        let mut min: Option<String> = None;
+        let mut max: Option<String> = None;
+        let mut vars: Vec<String> = Vec::new();
+
+        // Every field's value spans the segments until the next keyword,
+        // since names and string values may themselves contain spaces
+        let mut current: Option<(&str, Vec<&str>)> = None;
+        let mut fields: Vec<(&str, String)> = Vec::new();
+        for segment in segments {
+            if matches!(segment, "name" | "type" | "default" | "min" | "max" | "var") {
+                if let Some((keyword, values)) = current.take() {
+                    fields.push((keyword, values.join(" ")));
+                }
+                current = Some((segment, Vec::new()));
+            } else if let Some((_, values)) = current.as_mut() {
+                values.push(segment);
+            } else {
+                return Err(StockfishError::malformed(line, "unexpected segment in option line"));
+            }
+        }
+        if let Some((keyword, values)) = current.take() {
+            fields.push((keyword, values.join(" ")));
+        }
+
+        for (keyword, value) in fields {
+            match keyword {
+                "name" => name = Some(value),
+                "type" => type_descriptor = Some(value),
+                "default" => default = Some(value),
+                "min" => min = Some(value),
+                "max" => max = Some(value),
+                _ => vars.push(value),
+            }
+        }
+
+        let name = name.filter(|name| !name.is_empty())
+            .ok_or_else(|| StockfishError::malformed(line, "option line should have a name"))?;
+        let type_descriptor = type_descriptor
+            .ok_or_else(|| StockfishError::malformed(line, "option line should have a type"))?;
+
+        let parse_spin_value = |value: Option<String>, field: &str| -> Result<i64> {
+            value.and_then(|value| value.parse().ok())
+                .ok_or_else(|| StockfishError::malformed(line, &format!("spin option should have an integer {field}")))
+        };
+
+        let option_type = match type_descriptor.as_str() {
+            "check" => OptionType::Check {
+                default: match default.as_deref() {
+                    Some("true") => true,
+                    Some("false") => false,
+                    _ => return Err(StockfishError::malformed(line, "check option should default to true or false")),
+                },
+            },
+            "spin" => OptionType::Spin {
+                default: parse_spin_value(default, "default")?,
+                min: parse_spin_value(min, "min")?,
+                max: parse_spin_value(max, "max")?,
+            },
+            "combo" => OptionType::Combo {
+                default: default
+                    .ok_or_else(|| StockfishError::malformed(line, "combo option should have a default"))?,
+                vars,
+            },
+            "button" => OptionType::Button,
+            "string" => OptionType::String { default: default.unwrap_or_default() },
+            _ => return Err(StockfishError::malformed(line, "unknown option type")),
+        };
+
+        Ok(EngineOption::new(name, option_type))
+    }
+
+    /// Returns the name of the option, as declared by the engine.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns an [`OptionType`] describing the type of the option, its default
+    /// value, and its constraints.
+    #[must_use]
+    pub fn option_type(&self) -> &OptionType {
+        &self.option_type
+    }
+
+    /// Returns the value that the option currently holds, as far as this wrapper
+    /// knows. (Options are assumed to hold their default values until they are set.)
+    /// 
+    /// Returns [`None`] for button options.
+    #[must_use]
+    pub fn value(&self) -> Option<&OptionValue> {
+        self.value.as_ref()
+    }
+
+    /// Returns the default value of the option, or [`None`] for button options.
+    #[must_use]
+    pub fn default_value(&self) -> Option<OptionValue> {
+        self.option_type.default_value()
+    }
+
+    /// Checks that the given string is a valid value for this option, and returns
+    /// it as an [`OptionValue`].
+    /// 
+    /// # Example
+    /// 
+    /// ```rust
+    /// use stockfish::{EngineOption, OptionValue};
+    /// # fn main() -> Result<(), stockfish::StockfishError> {
+    /// let option = EngineOption::parse("option name Threads type spin default 1 min 1 max 1024")?;
+    /// assert_eq!(option.parse_value("8")?, OptionValue::Spin(8));
+    /// assert!(option.parse_value("0").is_err());
+    /// assert!(option.parse_value("eight").is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// 
+    /// # Errors
+    /// 
+    /// Returns [`StockfishError::InvalidOptionValue`] if the value doesn't match the
+    /// option's type or lies outside of its declared range. Button options don't
+    /// accept values; they should instead be pressed through
+    /// [`Stockfish::press_button`](crate::Stockfish::press_button).
+    pub fn parse_value(&self, value: &str) -> Result<OptionValue> {
+        let invalid = |reason: &str| StockfishError::InvalidOptionValue {
+            name: self.name.clone(),
+            value: value.to_owned(),
+            reason: reason.to_owned(),
+        };
+
+        match &self.option_type {
+            OptionType::Check { .. } => match value {
+                "true" => Ok(OptionValue::Check(true)),
+                "false" => Ok(OptionValue::Check(false)),
+                _ => Err(invalid("expected \"true\" or \"false\"")),
+            },
+            OptionType::Spin { min, max, .. } => {
+                let value: i64 = value.parse().map_err(|_| invalid("expected an integer"))?;
+                if value < *min || value > *max {
+                    return Err(invalid(&format!("expected a value between {min} and {max}")));
+                }
+                Ok(OptionValue::Spin(value))
+            }
+            OptionType::Combo { vars, .. } => vars.iter()
+                .find(|var| var.eq_ignore_ascii_case(value))
+                .map(|var| OptionValue::Combo(var.clone()))
+                .ok_or_else(|| invalid(&format!("expected one of {vars:?}"))),
+            OptionType::Button => Err(invalid("button options don't take a value")),
+            OptionType::String { .. } => {
+                if value.contains(['\n', '\r']) {
+                    return Err(invalid("string values can't contain line breaks"));
+                }
+                Ok(OptionValue::String(value.to_owned()))
+            }
+        }
+    }
+
+    pub(crate) fn set_value(&mut self, value: OptionValue) {
+        self.value = Some(value);
+    }
+}
+impl fmt::Display for EngineOption {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "option name {} type {}", self.name, self.option_type)?;
+        match &self.option_type {
+            OptionType::Check { default } => write!(f, " default {default}"),
+            OptionType::Spin { default, min, max } => write!(f, " default {default} min {min} max {max}"),
+            OptionType::Combo { default, vars } => {
+                write!(f, " default {default}")?;
+                vars.iter().try_for_each(|var| write!(f, " var {var}"))
+            }
+            OptionType::Button => Ok(()),
+            OptionType::String { default } => write!(f, " default {default}"),
+        }
+    }
+}
+
+/// The registry of options that the engine declared during the UCI handshake.
+/// Obtained through [`Stockfish::get_options`](crate::Stockfish::get_options).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EngineOptions {
+    options: Vec<EngineOption>,
+}
+
+impl EngineOptions {
+
+    #[must_use]
+    pub fn new(options: Vec<EngineOption>) -> Self {
+        Self { options }
+    }
+
+    /// Returns the option with the given name, if the engine declared one.
+    /// As in the UCI protocol, option names are matched case-insensitively.
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&EngineOption> {
+        self.options.iter().find(|option| option.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Returns `true` if the engine declared an option with the given name.
+    #[must_use]
+    pub fn contains(&self, name: &str) -> bool {
+        self.get(name).is_some()
+    }
+
+    /// Returns an iterator over the declared options, in the order in which
+    /// the engine declared them.
+    pub fn iter(&self) -> std::slice::Iter<'_, EngineOption> {
+        self.options.iter()
+    }
+
+    /// Returns the number of declared options.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.options.len()
+    }
+
+    /// Returns `true` if the engine declared no options.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.options.is_empty()
+    }
+
+    pub(crate) fn get_mut(&mut self, name: &str) -> Option<&mut EngineOption> {
+        self.options.iter_mut().find(|option| option.name.eq_ignore_ascii_case(name))
+    }
+
+    pub(crate) fn push(&mut self, option: EngineOption) {
+        self.options.push(option);
+    }
+}
+
+impl<'a> IntoIterator for &'a EngineOptions {
+    type Item = &'a EngineOption;
+    type IntoIter = std::slice::Iter<'a, EngineOption>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
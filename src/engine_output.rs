@@ -1,22 +1,30 @@
-use crate::engine_eval::EngineEval;
+use crate::engine_eval::{EngineEval, EvalType};
+use crate::error::Result;
+use crate::position::Position;
 use std::fmt;
 
 /// Represents the total output from the engine in regards to one specific position.
 /// Contains the engine's score evaluation of the position as well as its
 /// determined best move.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct EngineOutput {
     eval: EngineEval,
     best_move: String,
     pondered_move: Option<String>,
     depth: u32,
+    book_move: bool,
 }
 
 impl EngineOutput {
 
     #[must_use]
     pub fn new(eval: EngineEval, best_move: String, pondered_move: Option<String>, depth: u32) -> Self {
-        Self { eval, best_move, pondered_move, depth }
+        Self { eval, best_move, pondered_move, depth, book_move: false }
+    }
+
+    /// The output for a move taken from an opening book instead of a search.
+    pub(crate) fn from_book(best_move: String) -> Self {
+        Self { book_move: true, ..Self::new(EngineEval::new(EvalType::Centipawn, 0), best_move, None, 0) }
     }
 
     /// Returns [`EngineEval`], a struct representing the engine's
@@ -48,6 +56,41 @@ impl EngineOutput {
     pub fn depth(&self) -> u32 {
         self.depth
     }
+
+    /// Returns `true` if the best move was taken from the opening book set through
+    /// [`Stockfish::set_book`](crate::Stockfish::set_book), rather than searched for, in
+    /// which case the eval is 0 centipawns and the depth is 0.
+    #[must_use]
+    pub fn is_book_move(&self) -> bool {
+        self.book_move
+    }
+
+    /// Returns the best move in standard algebraic notation (e.g., `"Nf3"`), given the
+    /// position that the engine searched (e.g., [`Stockfish::get_position`](crate::Stockfish::get_position).)
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StockfishError::IllegalMove`](crate::StockfishError::IllegalMove) if the
+    /// move isn't legal in the given position.
+    pub fn best_move_san(&self, position: &Position) -> Result<String> {
+        position.uci_to_san(&self.best_move)
+    }
+
+    /// Returns the pondered move in standard algebraic notation, given the position that
+    /// the engine searched (before the best move is played.)
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StockfishError::IllegalMove`](crate::StockfishError::IllegalMove) if the
+    /// best move or the pondered move isn't legal in the given position.
+    pub fn pondered_move_san(&self, position: &Position) -> Result<Option<String>> {
+        let Some(pondered_move) = &self.pondered_move else {
+            return Ok(None);
+        };
+        let mut position = position.clone();
+        position.play_uci(&self.best_move)?;
+        position.uci_to_san(pondered_move).map(Some)
+    }
 }
 impl fmt::Display for EngineOutput {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
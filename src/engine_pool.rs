@@ -0,0 +1,246 @@
+use std::{
+    ops::{Deref, DerefMut},
+    sync::{Condvar, Mutex, PoisonError},
+    thread,
+};
+
+use crate::error::Result;
+use crate::stockfish::{Settings, Stockfish};
+
+/// The function that configures every engine spawned by an [`EnginePool`].
+type Setup = dyn Fn(&mut Stockfish) -> Result<()> + Send + Sync;
+
+/// A fixed-size pool of engine processes, for analyzing many positions in parallel.
+///
+/// Engines are either checked out one at a time through [`EnginePool::checkout`], or
+/// handed jobs through [`EnginePool::run`]. Before an engine is handed out, it is
+/// prepared for a new game (`"ucinewgame"`), reset to the starting position, and has
+/// its depth, option values, timeouts, watchdog and opening book restored to what they
+/// were once the engine was spawned and configured by the setup function, so that no
+/// state carries over from the previous job. An engine that has crashed (or fails to
+/// respond) is killed and replaced by a freshly spawned one.
+///
+/// # Example
+///
+/// ```no_run
+/// use stockfish::EnginePool;
+/// # fn main() -> Result<(), stockfish::StockfishError> {
+/// let pool = EnginePool::with_setup("stockfish.exe", 4, |stockfish| {
+///     stockfish.set_depth(18);
+///     stockfish.set_hash(64)
+/// })?;
+///
+/// let fens = [
+///     "r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3",
+///     "rnbqkb1r/pppp1ppp/5n2/4p3/4P3/2N5/PPPP1PPP/R1BQKBNR w KQkq - 2 3",
+/// ];
+/// let results = pool.run(fens, |stockfish, fen| {
+///     stockfish.set_fen_position(fen)?;
+///     stockfish.go()
+/// });
+/// for result in results {
+///     println!("{}", result?);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct EnginePool {
+    path: String,
+    size: usize,
+    setup: Box<Setup>,
+    /// The engines that aren't checked out. [`None`] marks a slot whose engine
+    /// couldn't be respawned, which is retried on the next checkout.
+    idle: Mutex<Vec<Option<Engine>>>,
+    returned: Condvar,
+}
+
+/// An engine of the pool, along with its settings as of when it was set up.
+struct Engine {
+    stockfish: Stockfish,
+    settings: Settings,
+}
+
+impl EnginePool {
+
+    /// Spawns `size` engines from the binary executable at the given path.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`StockfishError`](crate::StockfishError) if one of the engines
+    /// couldn't be created.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is 0.
+    pub fn new(path: &str, size: usize) -> Result<EnginePool> {
+        EnginePool::with_setup(path, size, |_| Ok(()))
+    }
+
+    /// Spawns `size` engines from the binary executable at the given path, and
+    /// configures each of them with the given function. (The function is also
+    /// applied to the engines that replace crashed ones; before each job, the
+    /// settings that it made are restored.)
+    ///
+    /// To have unresponsive engines replaced instead of blocking the pool, set timeouts
+    /// on each engine, e.g., through [`Stockfish::set_ensure_ready_timeout`] and
+    /// [`Stockfish::set_watchdog`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`StockfishError`](crate::StockfishError) if one of the engines
+    /// couldn't be created, or if the setup function failed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is 0.
+    pub fn with_setup<F>(path: &str, size: usize, setup: F) -> Result<EnginePool>
+    where
+        F: Fn(&mut Stockfish) -> Result<()> + Send + Sync + 'static,
+    {
+        assert!(size > 0, "an engine pool needs at least one engine");
+
+        let mut pool = EnginePool {
+            path: path.to_owned(),
+            size,
+            setup: Box::new(setup),
+            idle: Mutex::new(Vec::with_capacity(size)),
+            returned: Condvar::new(),
+        };
+
+        let engines = (0..size)
+            .map(|_| pool.spawn().map(Some))
+            .collect::<Result<Vec<_>>>()?;
+        *pool.idle.get_mut().unwrap_or_else(PoisonError::into_inner) = engines;
+        Ok(pool)
+    }
+
+    /// Returns the number of engines in the pool.
+    #[must_use]
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns the number of engines that aren't currently checked out.
+    #[must_use]
+    pub fn available(&self) -> usize {
+        self.idle.lock().unwrap_or_else(PoisonError::into_inner).len()
+    }
+
+    /// Checks out an engine, blocking until one is available. The engine is returned
+    /// to the pool once the [`PooledEngine`] is dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`StockfishError`](crate::StockfishError) if the engine had crashed,
+    /// and a replacement couldn't be spawned.
+    pub fn checkout(&self) -> Result<PooledEngine<'_>> {
+        let mut idle = self.idle.lock().unwrap_or_else(PoisonError::into_inner);
+        let slot = loop {
+            if let Some(slot) = idle.pop() {
+                break slot;
+            }
+            idle = self.returned.wait(idle).unwrap_or_else(PoisonError::into_inner);
+        };
+        drop(idle);
+
+        match self.prepare(slot) {
+            Ok(engine) => Ok(PooledEngine { pool: self, engine: Some(engine) }),
+            Err(err) => {
+                self.give_back(None);
+                Err(err)
+            }
+        }
+    }
+
+    /// Runs the given function on every job, spreading the jobs across the engines
+    /// of the pool. Each job gets a freshly reset engine. The results are returned in
+    /// the order of the jobs.
+    pub fn run<J, T, F>(&self, jobs: impl IntoIterator<Item = J>, job: F) -> Vec<Result<T>>
+    where
+        J: Send,
+        T: Send,
+        F: Fn(&mut Stockfish, J) -> Result<T> + Sync,
+    {
+        let jobs: Vec<J> = jobs.into_iter().collect();
+        let job_count = jobs.len();
+        let queue = Mutex::new(jobs.into_iter().enumerate());
+        let results = Mutex::new((0..job_count).map(|_| None).collect::<Vec<Option<Result<T>>>>());
+
+        thread::scope(|scope| {
+            for _ in 0..self.size.min(job_count) {
+                scope.spawn(|| loop {
+                    let next = queue.lock().unwrap_or_else(PoisonError::into_inner).next();
+                    let Some((index, input)) = next else {
+                        break;
+                    };
+                    let result = self.checkout().and_then(|mut engine| job(&mut engine, input));
+                    results.lock().unwrap_or_else(PoisonError::into_inner)[index] = Some(result);
+                });
+            }
+        });
+
+        results.into_inner().unwrap_or_else(PoisonError::into_inner)
+            .into_iter()
+            .map(|result| result.expect("every job should have been run"))
+            .collect()
+    }
+
+    /* Private Methods */
+
+    fn spawn(&self) -> Result<Engine> {
+        let mut stockfish = Stockfish::new(&self.path)?;
+        if let Err(err) = (self.setup)(&mut stockfish) {
+            stockfish.kill();
+            return Err(err);
+        }
+        let settings = stockfish.settings();
+        Ok(Engine { stockfish, settings })
+    }
+
+    /// Resets an engine for its next job, replacing it if it has crashed.
+    fn prepare(&self, slot: Option<Engine>) -> Result<Engine> {
+        if let Some(Engine { mut stockfish, settings }) = slot {
+            let reset = stockfish.setup_for_new_game()
+                .and_then(|()| stockfish.reset_position())
+                .and_then(|()| stockfish.restore_settings(&settings));
+            if stockfish.is_running() && reset.is_ok() {
+                return Ok(Engine { stockfish, settings });
+            }
+            // The engine has crashed or hung, and wouldn't necessarily exit on its own
+            stockfish.kill();
+        }
+        self.spawn()
+    }
+
+    fn give_back(&self, slot: Option<Engine>) {
+        self.idle.lock().unwrap_or_else(PoisonError::into_inner).push(slot);
+        self.returned.notify_one();
+    }
+}
+
+/// An engine checked out of an [`EnginePool`], which dereferences to [`Stockfish`].
+/// The engine is returned to the pool once this is dropped.
+pub struct PooledEngine<'a> {
+    pool: &'a EnginePool,
+    engine: Option<Engine>,
+}
+
+impl Deref for PooledEngine<'_> {
+    type Target = Stockfish;
+
+    fn deref(&self) -> &Stockfish {
+        &self.engine.as_ref().expect("engine should be present until dropped").stockfish
+    }
+}
+
+impl DerefMut for PooledEngine<'_> {
+    fn deref_mut(&mut self) -> &mut Stockfish {
+        &mut self.engine.as_mut().expect("engine should be present until dropped").stockfish
+    }
+}
+
+impl Drop for PooledEngine<'_> {
+    fn drop(&mut self) {
+        self.pool.give_back(self.engine.take());
+    }
+}
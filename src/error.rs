@@ -0,0 +1,193 @@
+use std::{error, fmt, io, time::Duration};
+
+/// The error type returned by the fallible operations of this crate.
+#[derive(Debug)]
+pub enum StockfishError {
+    /// An I/O error occurred while trying to create or communicate with the
+    /// engine process.
+    Io(io::Error),
+
+    /// The engine process exited (or closed its output stream) while a
+    /// response was still expected from it.
+    EngineExited,
+
+    /// The engine outputted a line that couldn't be parsed.
+    /// Contains the offending line and a description of what was expected.
+    MalformedOutput {
+        line: String,
+        reason: String,
+    },
+
+    /// A score descriptor other than `"cp"` or `"mate"` was encountered.
+    UnknownEvalDescriptor(String),
+
+    /// The engine didn't respond to a command within the configured timeout.
+    /// The engine process is left running: a timed-out search is stopped, and the
+    /// output still owed to the command is discarded before the next command that
+    /// awaits a response, so that it can't be mistaken for the response to that command.
+    Timeout {
+        command: String,
+        timeout: Duration,
+    },
+
+    /// The engine didn't respond to a command within the configured timeout, and
+    /// the watchdog killed the engine process as a result.
+    Unresponsive {
+        command: String,
+        timeout: Duration,
+    },
+
+    /// The engine didn't declare an option with the given name during the UCI handshake.
+    UnknownOption(String),
+
+    /// A value was rejected for an option, because it didn't match the type or range
+    /// that the engine declared for that option.
+    InvalidOptionValue {
+        name: String,
+        value: String,
+        reason: String,
+    },
+
+    /// The parameters given for a search were rejected, for the given reason.
+    InvalidGoParams(String),
+
+    /// A FEN was rejected before being sent to the engine.
+    /// Contains the offending FEN and the reason it was rejected.
+    IllegalPosition {
+        fen: String,
+        reason: String,
+    },
+
+    /// A move was rejected before being played on the board.
+    /// Contains the offending move, the ply at which it was to be played (counted
+    /// from 1 for white's first move, when known), and the reason it was rejected.
+    IllegalMove {
+        move_str: String,
+        ply: Option<u32>,
+        reason: String,
+    },
+
+    /// A PGN couldn't be parsed. Contains the number of the line on which the problem
+    /// was found, and a description of it.
+    InvalidPgn {
+        line: usize,
+        reason: String,
+    },
+
+    /// A move could denote several legal moves, and was rejected as a result.
+    /// Contains the offending move, the ply at which it was to be played (when known),
+    /// and the moves that it could denote, in standard algebraic notation.
+    AmbiguousMove {
+        move_str: String,
+        ply: Option<u32>,
+        candidates: Vec<String>,
+    },
+
+    /// A ply was given that lies past the end of a game's mainline.
+    /// Contains the offending ply, and the number of plies in the mainline.
+    PlyOutOfRange {
+        ply: usize,
+        plies: usize,
+    },
+
+    /// A Polyglot opening book couldn't be read, for the given reason.
+    InvalidBook(String),
+
+    /// The state saved by a tournament couldn't be resumed, because it is malformed or
+    /// doesn't match the tournament. Contains the path to the state file, and the reason.
+    InvalidTournamentState {
+        path: String,
+        reason: String,
+    },
+}
+
+impl StockfishError {
+    pub(crate) fn malformed(line: &str, reason: &str) -> Self {
+        StockfishError::MalformedOutput {
+            line: line.to_owned(),
+            reason: reason.to_owned(),
+        }
+    }
+
+    pub(crate) fn illegal_position(fen: &str, reason: &str) -> Self {
+        StockfishError::IllegalPosition {
+            fen: fen.to_owned(),
+            reason: reason.to_owned(),
+        }
+    }
+}
+
+impl fmt::Display for StockfishError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StockfishError::Io(err) => write!(f, "i/o error while communicating with the engine: {err}"),
+            StockfishError::EngineExited => write!(f, "the engine process exited unexpectedly"),
+            StockfishError::MalformedOutput { line, reason } => {
+                write!(f, "malformed engine output ({reason}): {line:?}")
+            }
+            StockfishError::UnknownEvalDescriptor(descriptor) => {
+                write!(f, "unknown eval descriptor: {descriptor:?}")
+            }
+            StockfishError::Timeout { command, timeout } => {
+                write!(f, "the engine didn't respond to {command:?} within {timeout:?}")
+            }
+            StockfishError::Unresponsive { command, timeout } => {
+                write!(f, "the engine didn't respond to {command:?} within {timeout:?} and was killed")
+            }
+            StockfishError::UnknownOption(name) => {
+                write!(f, "the engine has no option named {name:?}")
+            }
+            StockfishError::InvalidOptionValue { name, value, reason } => {
+                write!(f, "invalid value {value:?} for option {name:?} ({reason})")
+            }
+            StockfishError::InvalidGoParams(reason) => {
+                write!(f, "invalid search parameters: {reason}")
+            }
+            StockfishError::IllegalPosition { fen, reason } => {
+                write!(f, "illegal position ({reason}): {fen:?}")
+            }
+            StockfishError::IllegalMove { move_str, ply: Some(ply), reason } => {
+                write!(f, "illegal move {move_str:?} at ply {ply} ({reason})")
+            }
+            StockfishError::IllegalMove { move_str, ply: None, reason } => {
+                write!(f, "illegal move {move_str:?} ({reason})")
+            }
+            StockfishError::InvalidPgn { line, reason } => {
+                write!(f, "invalid PGN at line {line}: {reason}")
+            }
+            StockfishError::AmbiguousMove { move_str, ply, candidates } => {
+                write!(f, "ambiguous move {move_str:?}")?;
+                if let Some(ply) = ply {
+                    write!(f, " at ply {ply}")?;
+                }
+                write!(f, " (could be {})", candidates.join(", "))
+            }
+            StockfishError::PlyOutOfRange { ply, plies } => {
+                write!(f, "ply {ply} is out of range, as the game's mainline has {plies} plies")
+            }
+            StockfishError::InvalidBook(reason) => {
+                write!(f, "invalid opening book: {reason}")
+            }
+            StockfishError::InvalidTournamentState { path, reason } => {
+                write!(f, "invalid tournament state in {path:?}: {reason}")
+            }
+        }
+    }
+}
+
+impl error::Error for StockfishError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            StockfishError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for StockfishError {
+    fn from(err: io::Error) -> Self {
+        StockfishError::Io(err)
+    }
+}
+
+pub(crate) type Result<T> = std::result::Result<T, StockfishError>;
@@ -0,0 +1,209 @@
+use std::{fmt, time::Duration};
+
+/// A builder for the limits of a search, which may be combined freely and are
+/// passed to [`Stockfish::go_with`](crate::Stockfish::go_with).
+///
+/// Without a depth, node, mate, move time or clock limit, the engine would search until told
+/// to stop; [`Stockfish::go_with`](crate::Stockfish::go_with) limits such searches to the depth
+/// set through [`Stockfish::set_depth`](crate::Stockfish::set_depth) instead.
+///
+/// # Example
+///
+/// ```rust
+/// use std::time::Duration;
+/// use stockfish::GoParams;
+///
+/// let params = GoParams::new()
+///     .white_time(Duration::from_secs(60))
+///     .black_time(Duration::from_secs(55))
+///     .white_increment(Duration::from_secs(1))
+///     .black_increment(Duration::from_secs(1))
+///     .moves_to_go(20);
+///
+/// assert_eq!(params.to_string(), "go wtime 60000 btime 55000 winc 1000 binc 1000 movestogo 20");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GoParams {
+    search_moves: Vec<String>,
+    white_time: Option<Duration>,
+    black_time: Option<Duration>,
+    white_increment: Option<Duration>,
+    black_increment: Option<Duration>,
+    moves_to_go: Option<u32>,
+    depth: Option<u32>,
+    nodes: Option<u64>,
+    mate: Option<u32>,
+    move_time: Option<Duration>,
+    infinite: bool,
+    ponder: bool,
+}
+
+impl GoParams {
+
+    /// Creates a [`GoParams`] without any limits.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Restricts the search to the given moves (`searchmoves`), in long UCI
+    /// algebraic notation.
+    #[must_use]
+    pub fn search_moves(mut self, moves: &[&str]) -> Self {
+        self.search_moves = moves.iter().map(ToString::to_string).collect();
+        self
+    }
+
+    /// Sets the time that white has left on the clock (`wtime`).
+    #[must_use]
+    pub fn white_time(mut self, time: Duration) -> Self {
+        self.white_time = Some(time);
+        self
+    }
+
+    /// Sets the time that black has left on the clock (`btime`).
+    #[must_use]
+    pub fn black_time(mut self, time: Duration) -> Self {
+        self.black_time = Some(time);
+        self
+    }
+
+    /// Sets white's increment per move (`winc`).
+    #[must_use]
+    pub fn white_increment(mut self, increment: Duration) -> Self {
+        self.white_increment = Some(increment);
+        self
+    }
+
+    /// Sets black's increment per move (`binc`).
+    #[must_use]
+    pub fn black_increment(mut self, increment: Duration) -> Self {
+        self.black_increment = Some(increment);
+        self
+    }
+
+    /// Sets the number of moves left until the next time control (`movestogo`).
+    #[must_use]
+    pub fn moves_to_go(mut self, moves: u32) -> Self {
+        self.moves_to_go = Some(moves);
+        self
+    }
+
+    /// Limits the search to the given depth, in plies (`depth`).
+    #[must_use]
+    pub fn depth(mut self, depth: u32) -> Self {
+        self.depth = Some(depth);
+        self
+    }
+
+    /// Limits the search to the given number of nodes (`nodes`).
+    #[must_use]
+    pub fn nodes(mut self, nodes: u64) -> Self {
+        self.nodes = Some(nodes);
+        self
+    }
+
+    /// Makes the engine search for a mate in the given number of moves (`mate`).
+    #[must_use]
+    pub fn mate(mut self, moves: u32) -> Self {
+        self.mate = Some(moves);
+        self
+    }
+
+    /// Makes the engine search for exactly the given amount of time (`movetime`).
+    #[must_use]
+    pub fn move_time(mut self, time: Duration) -> Self {
+        self.move_time = Some(time);
+        self
+    }
+
+    /// Makes the engine search until it is told to stop (`infinite`). Such a search
+    /// should be started through [`Stockfish::start_search_with`](crate::Stockfish::start_search_with),
+    /// so that it can be stopped.
+    #[must_use]
+    pub fn infinite(mut self) -> Self {
+        self.infinite = true;
+        self
+    }
+
+    /// Returns `true` if the search only ends when the engine is told to stop.
+    #[must_use]
+    pub fn is_infinite(&self) -> bool {
+        self.infinite
+    }
+
+    /// Makes the engine search in ponder mode (`ponder`), on the position reached by the
+    /// opponent's expected reply. The limits only apply once the engine is told that the
+    /// reply was played, through [`SearchHandle::ponderhit`](crate::SearchHandle::ponderhit);
+    /// until then, the engine searches until it is told to stop. Such a search should be
+    /// started through [`Stockfish::start_search_with`](crate::Stockfish::start_search_with).
+    #[must_use]
+    pub fn ponder(mut self) -> Self {
+        self.ponder = true;
+        self
+    }
+
+    /// Returns `true` if the search is to be started in ponder mode.
+    #[must_use]
+    pub fn is_ponder(&self) -> bool {
+        self.ponder
+    }
+
+    /// Returns `true` if the search is restricted to some moves, or looks for a mate, in
+    /// which case a book move can't stand in for it.
+    pub(crate) fn is_restricted(&self) -> bool {
+        !self.search_moves.is_empty() || self.mate.is_some()
+    }
+
+    /// Returns `true` if the search is limited by depth, nodes, mate, move time or either
+    /// side's clock, so that it concludes without being told to stop.
+    pub(crate) fn is_bounded(&self) -> bool {
+        self.depth.is_some() || self.nodes.is_some() || self.mate.is_some() || self.move_time.is_some()
+            || self.white_time.is_some() || self.black_time.is_some()
+    }
+}
+impl fmt::Display for GoParams {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "go")?;
+        if self.ponder {
+            write!(f, " ponder")?;
+        }
+
+        let millis = |duration: Duration| duration.as_millis();
+        if let Some(time) = self.white_time {
+            write!(f, " wtime {}", millis(time))?;
+        }
+        if let Some(time) = self.black_time {
+            write!(f, " btime {}", millis(time))?;
+        }
+        if let Some(increment) = self.white_increment {
+            write!(f, " winc {}", millis(increment))?;
+        }
+        if let Some(increment) = self.black_increment {
+            write!(f, " binc {}", millis(increment))?;
+        }
+        if let Some(moves) = self.moves_to_go {
+            write!(f, " movestogo {moves}")?;
+        }
+        if let Some(depth) = self.depth {
+            write!(f, " depth {depth}")?;
+        }
+        if let Some(nodes) = self.nodes {
+            write!(f, " nodes {nodes}")?;
+        }
+        if let Some(moves) = self.mate {
+            write!(f, " mate {moves}")?;
+        }
+        if let Some(time) = self.move_time {
+            write!(f, " movetime {}", millis(time))?;
+        }
+        if self.infinite {
+            write!(f, " infinite")?;
+        }
+        // The moves must come last, as they extend until the end of the command
+        if !self.search_moves.is_empty() {
+            write!(f, " searchmoves {}", self.search_moves.join(" "))?;
+        }
+        Ok(())
+    }
+}
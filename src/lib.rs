@@ -1,20 +1,89 @@
 //! `stockfish` is a light, easy-to-use wrapper library for the Stockfish chess engine.
 //! 
 //! - **Creation & Setup** — Pass the path to the binary executable to [`Stockfish::new`],
-//! then call [`Stockfish::setup_for_new_game`] to ensure that it is ready for further
-//! commands.
+//!   then call [`Stockfish::setup_for_new_game`] to ensure that it is ready for further
+//!   commands.
+//! - **Options** — The options that the engine declares during the UCI handshake are
+//!   available through [`Stockfish::get_options`], and may be changed with
+//!   [`Stockfish::set_option`].
 //! - **Position** — Use methods like [`Stockfish::set_fen_position`] and 
-//! [`Stockfish::play_moves`] to configure the chess position that Stockfish is working with.
+//!   [`Stockfish::play_moves`] to configure the chess position that Stockfish is working with.
+//!   The position is tracked locally as a [`Position`], which validates FENs, generates legal
+//!   moves and applies them without consulting the engine; it may also be used on its own,
+//!   e.g. to convert moves between UCI and standard algebraic notation ([`Position::to_san`],
+//!   [`Position::parse_san`]), or to read moves as people type them ([`Position::parse_move`].)
+//! - **PGN** — Games are read from PGN as [`PgnGame`]s (or streamed from a file through a
+//!   [`PgnReader`]), and [`Stockfish::set_pgn_position`] sets Stockfish up at any of their plies.
+//!   Games may also be built from engine play, annotated with evaluations, clock times and
+//!   variations, and exported through [`PgnGame::to_pgn`].
+//! - **Opening books** — A [`PolyglotBook`] reads Polyglot `.bin` books and looks up the weighted
+//!   moves for a position by its [`Position::polyglot_key`]; given to [`Stockfish::set_book`],
+//!   it has the engine play book moves before it falls back to searching. Books are built from
+//!   games or from the engine's MultiPV analysis through a [`BookBuilder`].
 //! - **Compute** — Using methods such as [`Stockfish::go`] or [`Stockfish::go_for`], 
-//! prompt Stockfish to start calculating given the current chess position.
-//! - **Output** — Accessory types have been included ([`EngineEval`], [`EvalType`], [`EngineOutput`])
-//! to structure the output from Stockfish after it has concluded its calculations.
+//!   prompt Stockfish to start calculating given the current chess position. Any combination
+//!   of search limits may be described through [`GoParams`] and passed to [`Stockfish::go_with`]. To keep the
+//!   calling thread free while Stockfish calculates, use [`Stockfish::start_search`].
+//! - **Output** — Accessory types have been included ([`EngineEval`], [`EvalType`], [`EngineOutput`], [`PvLine`])
+//!   to structure the output from Stockfish after it has concluded its calculations.
+//!   The progress of a search may be followed as it happens through [`Stockfish::go_streaming`],
+//!   which yields each of the engine's `info` lines as a [`SearchInfo`].
+//! - **Parallelism** — An [`EnginePool`] spawns several engines and spreads jobs across them,
+//!   resetting each engine between jobs and replacing those that crash.
+//! - **Matches** — An [`EngineMatch`] plays games between two [`EngineConfig`]s with a
+//!   [`TimeControl`], alternating colors over a set of openings and adjudicating games as
+//!   configured, and reports the results with an estimated Elo difference. A [`Sprt`] stops
+//!   a match as soon as it can tell whether one engine is stronger than the other.
+//! - **Tournaments** — A [`Tournament`] ranks several engine configurations in a round-robin
+//!   or gauntlet, playing games concurrently, saving its progress to disk so that it may be
+//!   resumed, and reporting standings and a crosstable as text or JSON.
+//! - **Async** — With the `async` cargo feature enabled, [`AsyncStockfish`] offers the same
+//!   operations as futures built on tokio's child-process I/O.
+//! - **Errors** — Every fallible operation returns a [`StockfishError`], which describes
+//!   what went wrong (including the offending engine output, where applicable.)
 
 mod stockfish;
+#[cfg(feature = "async")]
+mod async_stockfish;
 
+mod book_builder;
+mod elo;
 mod engine_eval;
+mod engine_match;
+mod engine_options;
+mod engine_pool;
 mod engine_output;
+mod error;
+mod go_params;
+mod move_parser;
+mod pgn;
+mod polyglot;
+mod position;
+mod pv_line;
+mod san;
+mod search;
+mod search_info;
+mod sprt;
+mod tournament;
 
 pub use crate::stockfish::Stockfish;
+#[cfg(feature = "async")]
+pub use crate::async_stockfish::AsyncStockfish;
+pub use crate::book_builder::{BookBuilder, BookWeighting};
 pub use crate::engine_eval::{EngineEval, EvalType};
-pub use crate::engine_output::EngineOutput;
\ No newline at end of file
+pub use crate::engine_match::{
+    Adjudication, EngineConfig, EngineMatch, GameRecord, GameResult, MatchResult, Termination, TimeControl,
+};
+pub use crate::engine_options::{EngineOption, EngineOptions, OptionType, OptionValue};
+pub use crate::engine_pool::{EnginePool, PooledEngine};
+pub use crate::engine_output::EngineOutput;
+pub use crate::error::StockfishError;
+pub use crate::go_params::GoParams;
+pub use crate::pgn::{PgnGame, PgnMove, PgnReader};
+pub use crate::polyglot::{BookEntry, BookSelection, PolyglotBook};
+pub use crate::position::{CastlingSide, Color, Move, Piece, PieceKind, Position, Square};
+pub use crate::pv_line::PvLine;
+pub use crate::search::{SearchEvent, SearchHandle, SearchStopper, SearchStream};
+pub use crate::search_info::{Score, ScoreBound, SearchInfo, Wdl};
+pub use crate::sprt::{Sprt, SprtStatus};
+pub use crate::tournament::{Standing, Tournament, TournamentFormat, TournamentResult};
\ No newline at end of file
@@ -0,0 +1,257 @@
+use crate::error::{Result, StockfishError};
+use crate::position::{Color, Move, PieceKind, Position, Square};
+use crate::san::SanMove;
+
+impl Position {
+    /// Resolves a move typed by a person to the legal move that it denotes.
+    ///
+    /// Besides long UCI algebraic notation (`"e2e4"`) and standard algebraic notation
+    /// (`"Nf3"`), this accepts the usual variations on them: separators (`"e2-e4"`,
+    /// `"e4xd5"`), origin squares (`"Ng1f3"`), lowercase piece letters (`"nf3"`), a `P` for
+    /// pawns (`"Pe4"`), castling with zeros or without hyphens (`"0-0"`, `"OOO"`), the
+    /// king moving onto its own rook to castle (`"e1h1"`), promotions without `"="`
+    /// (`"e8Q"`), figurines (`"♘f3"`), and trailing annotations (`"+"`, `"!?"`, `"e.p."`).
+    /// Input that is a legal move in long UCI algebraic notation, or valid standard algebraic
+    /// notation, is read as such (so `"b1d2"` moves the piece on b1, and `"bxc3"` is a pawn
+    /// capture); otherwise, every reading of the input is considered.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use stockfish::{Position, StockfishError};
+    /// # fn main() -> Result<(), StockfishError> {
+    /// let mut position = Position::starting();
+    /// for input in ["e2-e4", "e5", "Ng1-f3", "nc6", "Bf1c4", "Bc5", "0-0"] {
+    ///     position.play_move(input)?;
+    /// }
+    /// assert_eq!(position.to_fen(), "r1bqk1nr/pppp1ppp/2n5/2b1p3/2B1P3/5N2/PPPP1PPP/RNBQ1RK1 b kq - 5 4");
+    ///
+    /// let Err(StockfishError::AmbiguousMove { candidates, .. }) = position.parse_move("Ne7") else {
+    ///     panic!("both black knights may go to e7");
+    /// };
+    /// assert_eq!(candidates, ["Nce7", "Nge7"]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StockfishError::AmbiguousMove`] listing the candidates if the input could
+    /// denote several legal moves, or [`StockfishError::IllegalMove`] if it can't be read
+    /// as a move, or if it doesn't denote any legal move (in which case the reason lists the
+    /// legal moves to the same square, if any.)
+    pub fn parse_move(&self, input: &str) -> Result<Move> {
+        if let Ok(mv) = Move::parse(input.trim())
+            && self.is_legal(mv)
+        {
+            return Ok(mv);
+        }
+        if let Some(san) = SanMove::parse(input.trim())
+            && let [mv] = san.candidates(self).as_slice()
+        {
+            return Ok(*mv);
+        }
+
+        let Some(reading) = Reading::parse(self, input) else {
+            return Err(self.rejected_input(input, "expected a move such as \"e4\", \"Nf3\" or \"e2e4\"".to_owned()));
+        };
+        let mut candidates: Vec<Move> = Vec::new();
+        for interpretation in &reading.interpretations {
+            for mv in interpretation.candidates(self) {
+                if !candidates.contains(&mv) {
+                    candidates.push(mv);
+                }
+            }
+        }
+
+        match candidates.as_slice() {
+            [mv] => Ok(*mv),
+            [] => Err(self.rejected_input(input, self.explain_unmatched(&reading))),
+            _ => Err(self.ambiguous_move(input, &candidates)),
+        }
+    }
+
+    /// Plays a move typed by a person, as read by [`Position::parse_move`], on the position.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StockfishError::AmbiguousMove`] or [`StockfishError::IllegalMove`] if the
+    /// input doesn't denote exactly one legal move, in which case the position is left unchanged.
+    pub fn play_move(&mut self, input: &str) -> Result<()> {
+        let mv = self.parse_move(input)?;
+        self.play(mv)
+    }
+
+    /* Private Methods */
+
+    /// Describes why no legal move matches the given reading of the input.
+    fn explain_unmatched(&self, reading: &Reading) -> String {
+        if let Some(from) = reading.from {
+            let explanation = self.clone().play(Move::new(from, reading.to, reading.promotion));
+            if let Err(StockfishError::IllegalMove { reason, .. }) = explanation {
+                return reason;
+            }
+            if let Some(piece) = self.piece_at(from) {
+                return format!("the piece on {from} is a {}", piece.kind().name());
+            }
+        }
+
+        let to = reading.to;
+        let alternatives: Vec<String> = self.legal_moves().into_iter()
+            .filter(|mv| mv.to() == to)
+            .map(|mv| self.to_san(mv).expect("legal moves should be formattable"))
+            .collect();
+        if alternatives.is_empty() {
+            format!("no legal move goes to {to}")
+        } else {
+            format!("no legal move matches it; the legal moves to {to} are {}", alternatives.join(", "))
+        }
+    }
+
+    fn rejected_input(&self, input: &str, reason: String) -> StockfishError {
+        StockfishError::IllegalMove {
+            move_str: input.to_owned(),
+            ply: Some(self.ply()),
+            reason,
+        }
+    }
+}
+
+/// The ways in which a move typed by a person may be read.
+struct Reading {
+    interpretations: Vec<SanMove>,
+    /// The origin square, if the input spelled it out in full.
+    from: Option<Square>,
+    to: Square,
+    promotion: Option<PieceKind>,
+}
+
+impl Reading {
+    fn parse(position: &Position, input: &str) -> Option<Reading> {
+        let mut input: String = input.trim().chars().map(figurine_letter).collect();
+        for suffix in ["e.p.", "ep"] {
+            if input.to_ascii_lowercase().ends_with(suffix) {
+                input.truncate(input.len() - suffix.len());
+            }
+        }
+        let input = input.trim_end_matches(|char: char| "+#!?".contains(char) || char.is_whitespace());
+
+        let castling: String = input.chars()
+            .filter(|&char| char != '-' && !char.is_whitespace())
+            .map(|char| if char == '0' {'o'} else {char.to_ascii_lowercase()})
+            .collect();
+        if castling == "oo" || castling == "ooo" {
+            let king_side = castling == "oo";
+            let back_rank = if position.side_to_move() == Color::White {0} else {7};
+            let to = Square::new(if king_side {6} else {2}, back_rank);
+            return Some(Reading {
+                interpretations: vec![SanMove::Castle { king_side }],
+                from: Some(Square::new(4, back_rank)),
+                to,
+                promotion: None,
+            });
+        }
+
+        let mut chars: Vec<char> = input.chars()
+            .filter(|&char| !"-:xX×=() ".contains(char))
+            .collect();
+
+        let mut promotion = None;
+        if chars.len() >= 3
+            && chars[chars.len() - 2].is_ascii_digit()
+            && let Some(kind) = PieceKind::from_char(chars[chars.len() - 1].to_ascii_lowercase())
+                .filter(|kind| !matches!(kind, PieceKind::Pawn | PieceKind::King))
+        {
+            promotion = Some(kind);
+            chars.pop();
+        }
+
+        let to = square(&chars[chars.len().checked_sub(2)?..])?;
+        let prefix = &chars[..chars.len() - 2];
+
+        // A leading lowercase "b" may be a bishop or the b-file
+        let mut readings: Vec<(PieceKind, Option<char>, &[char])> = Vec::new();
+        match prefix.first() {
+            Some(&letter) if "PNBRQK".contains(letter) || "pnrqk".contains(letter) => {
+                readings.push((PieceKind::from_char(letter.to_ascii_lowercase())?, Some(letter), &prefix[1..]));
+            }
+            Some('b') => {
+                readings.push((PieceKind::Bishop, Some('b'), &prefix[1..]));
+                readings.push((PieceKind::Pawn, None, prefix));
+            }
+            _ => readings.push((PieceKind::Pawn, None, prefix)),
+        }
+
+        let mut from = None;
+        let mut interpretations = Vec::new();
+        for (kind, letter, disambiguation) in readings {
+            let (from_file, from_rank) = match disambiguation {
+                [] => (None, None),
+                [file @ 'a'..='h'] => (Some(*file as u8 - b'a'), None),
+                [rank @ '1'..='8'] => (None, Some(*rank as u8 - b'1')),
+                [_, _] => {
+                    let Some(square) = square(disambiguation) else {
+                        continue;
+                    };
+                    (Some(square.file()), Some(square.rank()))
+                }
+                _ => continue,
+            };
+
+            let mut kind = kind;
+            if let (Some(file), Some(rank)) = (from_file, from_rank) {
+                let square = Square::new(file, rank);
+                from = Some(square);
+                if let Some(piece) = position.piece_at(square) {
+                    // Coordinates alone name whichever piece stands on the origin square
+                    if letter.is_none() {
+                        kind = piece.kind();
+                    }
+                    let onto_own_rook = position.piece_at(to)
+                        .is_some_and(|target| target.color() == piece.color() && target.kind() == PieceKind::Rook);
+                    let castles = onto_own_rook || file.abs_diff(to.file()) == 2;
+                    if kind == PieceKind::King && castles && to.rank() == rank {
+                        interpretations.push(SanMove::Castle { king_side: to.file() > file });
+                    }
+                }
+            }
+
+            let promotions: Vec<Option<PieceKind>> = if kind == PieceKind::Pawn && promotion.is_none() {
+                let mut promotions = vec![None];
+                promotions.extend(PieceKind::PROMOTIONS.map(Some));
+                promotions
+            } else {
+                vec![promotion]
+            };
+            for promotion in promotions {
+                interpretations.push(SanMove::Normal { kind, from_file, from_rank, to, promotion });
+            }
+        }
+
+        if interpretations.is_empty() {
+            return None;
+        }
+        Some(Reading { interpretations, from, to, promotion })
+    }
+}
+
+/// Reads a square from its file letter and rank digit.
+fn square(chars: &[char]) -> Option<Square> {
+    let [file, rank] = chars else {
+        return None;
+    };
+    Square::from_name(&format!("{}{rank}", file.to_ascii_lowercase()))
+}
+
+/// Replaces a chess figurine with the letter of the corresponding piece.
+fn figurine_letter(char: char) -> char {
+    match char {
+        '♔' | '♚' => 'K',
+        '♕' | '♛' => 'Q',
+        '♖' | '♜' => 'R',
+        '♗' | '♝' => 'B',
+        '♘' | '♞' => 'N',
+        '♙' | '♟' => 'P',
+        char => char,
+    }
+}
@@ -0,0 +1,1012 @@
+use std::{collections::VecDeque, fmt, io::BufRead, iter::Peekable, time::Duration, vec};
+
+use crate::engine_eval::{EngineEval, EvalType};
+use crate::error::{Result, StockfishError};
+use crate::position::{Color, Move, Position};
+
+/// The tags that every exported game starts with, in order, along with their default values.
+const SEVEN_TAG_ROSTER: [(&str, &str); 7] = [
+    ("Event", "?"),
+    ("Site", "?"),
+    ("Date", "????.??.??"),
+    ("Round", "?"),
+    ("White", "?"),
+    ("Black", "?"),
+    ("Result", "*"),
+];
+
+/// The maximum length of the lines of exported movetext.
+const LINE_LENGTH: usize = 80;
+
+/// A chess game as recorded in PGN: its headers (tag pairs), the position it started
+/// from, its moves with their comments, NAGs and variations, and its result.
+///
+/// Games are either read from PGN, or built move by move (e.g., from engine play), and
+/// are exported back to PGN through [`PgnGame::to_pgn`]. Use
+/// [`Stockfish::set_pgn_position`](crate::Stockfish::set_pgn_position) to have the
+/// engine analyze a game at any ply.
+///
+/// # Example
+///
+/// ```rust
+/// use stockfish::{PgnGame, StockfishError};
+/// # fn main() -> Result<(), StockfishError> {
+/// let game = PgnGame::parse(r#"
+/// [Event "Casual game"]
+/// [White "Anderssen"]
+/// [Black "Kieseritzky"]
+/// [Result "1-0"]
+///
+/// 1. e4 e5 2. f4 {The King's Gambit.} exf4 3. Bc4 Qh4+ 4. Kf1 b5?! (4... d6) 1-0
+/// "#)?;
+///
+/// assert_eq!(game.header("White"), Some("Anderssen"));
+/// assert_eq!(game.result(), "1-0");
+/// assert_eq!(game.moves().len(), 8);
+/// assert_eq!(game.moves()[2].comment(), Some("The King's Gambit."));
+/// assert_eq!(game.moves()[7].san(), "b5");
+/// assert_eq!(game.moves()[7].nags(), [6]);
+/// assert_eq!(game.moves()[7].variations()[0][0].san(), "d6");
+/// assert_eq!(game.position_at(4)?.to_fen(), "rnbqkbnr/pppp1ppp/8/8/4Pp2/8/PPPP2PP/RNBQKBNR w KQkq - 0 3");
+/// assert!(matches!(game.position_at(9), Err(StockfishError::PlyOutOfRange { ply: 9, plies: 8 })));
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Building and exporting a game:
+///
+/// ```rust
+/// use std::time::Duration;
+/// use stockfish::{EngineEval, EvalType, PgnGame};
+/// # fn main() -> Result<(), stockfish::StockfishError> {
+/// let mut game = PgnGame::new();
+/// game.set_header("White", "Stockfish");
+/// game.set_header("Black", "Stockfish");
+///
+/// game.push_move("e2e4")?.set_eval(Some(EngineEval::new(EvalType::Centipawn, 31)));
+/// game.push_move("c7c5")?.set_clock(Some(Duration::from_secs(58)));
+/// game.push_move("g1f3")?.add_nag(1);
+/// game.add_variation(2, &["b1c3", "b8c6"])?;
+/// game.set_result("*");
+///
+/// assert_eq!(game.to_pgn(), r#"[Event "?"]
+/// [Site "?"]
+/// [Date "????.??.??"]
+/// [Round "?"]
+/// [White "Stockfish"]
+/// [Black "Stockfish"]
+/// [Result "*"]
+///
+/// 1. e4 { [%eval 0.31] } 1... c5 { [%clk 0:00:58] } 2. Nf3 $1 ( 2. Nc3 Nc6 ) *
+/// "#);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct PgnGame {
+    headers: Vec<(String, String)>,
+    root_fen: Option<String>,
+    root_position: Position,
+    comment: Option<String>,
+    moves: Vec<PgnMove>,
+    /// The position at the end of the mainline.
+    position: Position,
+    result: String,
+}
+
+impl PgnGame {
+
+    /// Creates a game without moves or headers, starting from the standard starting position.
+    #[must_use]
+    pub fn new() -> PgnGame {
+        PgnGame {
+            headers: Vec::new(),
+            root_fen: None,
+            root_position: Position::starting(),
+            comment: None,
+            moves: Vec::new(),
+            position: Position::starting(),
+            result: "*".to_owned(),
+        }
+    }
+
+    /// Creates a game without moves or headers, starting from the position given by the
+    /// FEN. (The `[SetUp]` and `[FEN]` tags are added when the game is exported.)
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StockfishError::IllegalPosition`] if the FEN is malformed or illegal.
+    pub fn from_fen(fen: &str) -> Result<PgnGame> {
+        let position = Position::from_fen(fen)?;
+        let mut game = PgnGame::new();
+        if position != game.root_position {
+            game.root_fen = Some(fen.to_owned());
+            game.root_position = position.clone();
+            game.position = position;
+        }
+        Ok(game)
+    }
+
+    /// Creates a game from a root position (the standard starting position if [`None`]) and
+    /// the moves played from it, e.g. those of
+    /// [`Stockfish::get_root_fen`](crate::Stockfish::get_root_fen) and
+    /// [`Stockfish::get_move_history`](crate::Stockfish::get_move_history).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StockfishError::IllegalPosition`] if the FEN is malformed or illegal, or
+    /// [`StockfishError::IllegalMove`] if one of the moves is.
+    pub fn from_moves<S: AsRef<str>>(root_fen: Option<&str>, moves: &[S]) -> Result<PgnGame> {
+        let mut game = match root_fen {
+            Some(fen) => PgnGame::from_fen(fen)?,
+            None => PgnGame::new(),
+        };
+        for move_str in moves {
+            game.push_move(move_str.as_ref())?;
+        }
+        Ok(game)
+    }
+
+    /// Parses the first game of the given PGN.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StockfishError::InvalidPgn`] if the PGN contains no game, or if the first
+    /// game is malformed (including if it has an illegal move or an illegal `[FEN]` tag.)
+    pub fn parse(pgn: &str) -> Result<PgnGame> {
+        let mut tokens = Tokenizer::new(pgn, 1).peekable();
+        match PgnGame::parse_next(&mut tokens)? {
+            Some(game) => Ok(game),
+            None => Err(StockfishError::InvalidPgn { line: 1, reason: "no game found".to_owned() }),
+        }
+    }
+
+    /// Parses every game of the given PGN, in order.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StockfishError::InvalidPgn`] if one of the games is malformed.
+    pub fn parse_all(pgn: &str) -> Result<Vec<PgnGame>> {
+        let mut tokens = Tokenizer::new(pgn, 1).peekable();
+        let mut games = Vec::new();
+        while let Some(game) = PgnGame::parse_next(&mut tokens)? {
+            games.push(game);
+        }
+        Ok(games)
+    }
+
+    /// Returns the headers (tag pairs) of the game, in the order in which they appeared.
+    #[must_use]
+    pub fn headers(&self) -> &[(String, String)] {
+        &self.headers
+    }
+
+    /// Returns the value of the header with the given name, if present.
+    #[must_use]
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter()
+            .find(|(header, _)| header == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Sets the value of the header with the given name, replacing its previous value
+    /// (or adding the header after the others.) Setting the `"Result"` header sets the
+    /// result of the game.
+    pub fn set_header(&mut self, name: &str, value: &str) {
+        if name == "Result" {
+            value.clone_into(&mut self.result);
+        }
+        match self.headers.iter_mut().find(|(header, _)| header == name) {
+            Some((_, previous)) => value.clone_into(previous),
+            None => self.headers.push((name.to_owned(), value.to_owned())),
+        }
+    }
+
+    /// Returns the FEN given by the game's `[FEN]` tag, or [`None`] if the game starts
+    /// from the standard starting position.
+    #[must_use]
+    pub fn root_fen(&self) -> Option<&str> {
+        self.root_fen.as_deref()
+    }
+
+    /// Returns the position from which the game started.
+    #[must_use]
+    pub fn root_position(&self) -> &Position {
+        &self.root_position
+    }
+
+    /// Returns the comment that preceded the first move, if any.
+    #[must_use]
+    pub fn comment(&self) -> Option<&str> {
+        self.comment.as_deref()
+    }
+
+    /// Sets the comment that precedes the first move.
+    pub fn set_comment(&mut self, comment: Option<&str>) {
+        self.comment = comment.map(clean_comment);
+    }
+
+    /// Returns the moves of the game's mainline.
+    #[must_use]
+    pub fn moves(&self) -> &[PgnMove] {
+        &self.moves
+    }
+
+    /// Returns the moves of the game's mainline, so that they may be annotated.
+    #[must_use]
+    pub fn moves_mut(&mut self) -> &mut [PgnMove] {
+        &mut self.moves
+    }
+
+    /// Plays a move at the end of the game's mainline, and returns it so that it may be
+    /// annotated. The move is read as with [`Position::parse_move`], so it may be given
+    /// in long UCI algebraic notation (as the engine outputs it) or in standard algebraic
+    /// notation.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StockfishError::AmbiguousMove`] or [`StockfishError::IllegalMove`] if the
+    /// move doesn't denote exactly one legal move at the end of the game.
+    pub fn push_move(&mut self, move_str: &str) -> Result<&mut PgnMove> {
+        let mv = self.position.parse_move(move_str)?;
+        let pgn_move = PgnMove::new(mv, self.position.to_san(mv)?);
+        self.position.play(mv)?;
+        self.moves.push(pgn_move);
+        Ok(self.moves.last_mut().expect("a move was just pushed"))
+    }
+
+    /// Adds a variation (e.g., an engine's principal variation) as an alternative to the
+    /// move at the given ply of the mainline (counted from 0), and returns its moves so that
+    /// they may be annotated. The moves are read as with [`PgnGame::push_move`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StockfishError::PlyOutOfRange`] if there is no move at the given ply, or
+    /// [`StockfishError::AmbiguousMove`] or [`StockfishError::IllegalMove`] if one of the
+    /// moves doesn't denote exactly one legal move, in which case the game is left unchanged.
+    pub fn add_variation<S: AsRef<str>>(&mut self, ply: usize, moves: &[S]) -> Result<&mut [PgnMove]> {
+        if ply >= self.moves.len() {
+            return Err(StockfishError::PlyOutOfRange { ply, plies: self.moves.len() });
+        }
+        let mut position = self.position_at(ply)?;
+        let mut variation = Vec::with_capacity(moves.len());
+        for move_str in moves {
+            let mv = position.parse_move(move_str.as_ref())?;
+            variation.push(PgnMove::new(mv, position.to_san(mv)?));
+            position.play(mv)?;
+        }
+
+        let variations = &mut self.moves[ply].variations;
+        variations.push(variation);
+        Ok(variations.last_mut().expect("a variation was just pushed"))
+    }
+
+    /// Returns the result of the game: `"1-0"`, `"0-1"`, `"1/2-1/2"`, or `"*"` if
+    /// the game is unfinished or its result is unknown.
+    #[must_use]
+    pub fn result(&self) -> &str {
+        &self.result
+    }
+
+    /// Sets the result of the game (and its `"Result"` header), which should be one of
+    /// `"1-0"`, `"0-1"`, `"1/2-1/2"` or `"*"`.
+    pub fn set_result(&mut self, result: &str) {
+        self.set_header("Result", result);
+    }
+
+    /// Returns the moves of the game's mainline in long UCI algebraic notation.
+    #[must_use]
+    pub fn uci_moves(&self) -> Vec<String> {
+        self.moves.iter().map(|pgn_move| pgn_move.mv.to_string()).collect()
+    }
+
+    /// Returns the position after the given number of plies of the mainline have been
+    /// played (the root position for 0.)
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StockfishError::PlyOutOfRange`] if `ply` exceeds the number of moves in
+    /// the mainline.
+    pub fn position_at(&self, ply: usize) -> Result<Position> {
+        let Some(moves) = self.moves.get(..ply) else {
+            return Err(StockfishError::PlyOutOfRange { ply, plies: self.moves.len() });
+        };
+        let mut position = self.root_position.clone();
+        for pgn_move in moves {
+            position.play(pgn_move.mv).expect("the moves of a game should be legal");
+        }
+        Ok(position)
+    }
+
+    /// Exports the game to PGN, in the export format: the seven tag roster comes first,
+    /// NAGs are written as `$` glyphs, and movetext lines are at most 80 characters long.
+    /// The evaluation and clock of each move are written as `[%eval ...]` and `[%clk ...]`
+    /// commands in its comment.
+    #[must_use]
+    pub fn to_pgn(&self) -> String {
+        self.to_string()
+    }
+
+    /* Private Methods */
+
+    /// Parses the next game from the tokens, or returns [`None`] if there are none left.
+    fn parse_next(tokens: &mut Peekable<Tokenizer>) -> Result<Option<PgnGame>> {
+        let mut headers = Vec::new();
+        let mut line = 1;
+        while let Some(token) = tokens.next_if(|token| matches!(token, Ok((Token::Tag(..), _)))) {
+            let (Token::Tag(name, value), tag_line) = token? else {
+                unreachable!("only tags are taken");
+            };
+            line = tag_line;
+            headers.push((name, value));
+        }
+        if headers.is_empty() && tokens.peek().is_none() {
+            return Ok(None);
+        }
+
+        let root_fen = headers.iter()
+            .find(|(name, _)| name == "FEN")
+            .map(|(_, fen)| fen.clone());
+        let root_position = match &root_fen {
+            Some(fen) => Position::from_fen(fen)
+                .map_err(|err| StockfishError::InvalidPgn { line, reason: err.to_string() })?,
+            None => Position::starting(),
+        };
+
+        let mut result = None;
+        let (comment, moves, position) = parse_line(tokens, root_position.clone(), false, &mut result)?;
+        let result = result
+            .or_else(|| headers.iter().find(|(name, _)| name == "Result").map(|(_, result)| result.clone()))
+            .unwrap_or_else(|| "*".to_owned());
+        Ok(Some(PgnGame { headers, root_fen, root_position, comment, moves, position, result }))
+    }
+}
+
+impl Default for PgnGame {
+    fn default() -> Self {
+        PgnGame::new()
+    }
+}
+
+impl fmt::Display for PgnGame {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (name, default) in SEVEN_TAG_ROSTER {
+            let value = if name == "Result" {Some(self.result.as_str())} else {self.header(name)};
+            write_tag(f, name, value.unwrap_or(default))?;
+        }
+        if let Some(fen) = &self.root_fen {
+            write_tag(f, "SetUp", "1")?;
+            write_tag(f, "FEN", fen)?;
+        }
+        for (name, value) in &self.headers {
+            let exported = SEVEN_TAG_ROSTER.iter().any(|(roster_name, _)| roster_name == name);
+            if !exported && name != "SetUp" && name != "FEN" {
+                write_tag(f, name, value)?;
+            }
+        }
+        writeln!(f)?;
+
+        let mut tokens = Vec::new();
+        if let Some(comment) = &self.comment {
+            tokens.push(format!("{{ {comment} }}"));
+        }
+        write_line(&mut tokens, &self.moves, self.root_position.clone());
+        tokens.push(self.result.clone());
+
+        // Wrap the movetext, breaking lines only between words
+        let mut line_length = 0;
+        for word in tokens.join(" ").split(' ') {
+            if line_length > 0 && line_length + 1 + word.len() > LINE_LENGTH {
+                writeln!(f)?;
+                line_length = 0;
+            } else if line_length > 0 {
+                write!(f, " ")?;
+                line_length += 1;
+            }
+            write!(f, "{word}")?;
+            line_length += word.len();
+        }
+        writeln!(f)
+    }
+}
+
+fn write_tag(f: &mut fmt::Formatter, name: &str, value: &str) -> fmt::Result {
+    let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+    writeln!(f, "[{name} \"{value}\"]")
+}
+
+/// Writes the tokens of a line of moves that starts from the given position.
+fn write_line(tokens: &mut Vec<String>, moves: &[PgnMove], mut position: Position) {
+    // Black's moves need their number when they don't directly follow white's
+    let mut needs_number = true;
+    for pgn_move in moves {
+        if let Some(comment) = &pgn_move.starting_comment {
+            tokens.push(format!("{{ {comment} }}"));
+        }
+        let number = position.fullmove_number();
+        match position.side_to_move() {
+            Color::White => tokens.push(format!("{number}. {}", pgn_move.san)),
+            Color::Black if needs_number => tokens.push(format!("{number}... {}", pgn_move.san)),
+            Color::Black => tokens.push(pgn_move.san.clone()),
+        }
+        needs_number = false;
+
+        tokens.extend(pgn_move.nags.iter().map(|nag| format!("${nag}")));
+        let comment = pgn_move.exported_comment();
+        if !comment.is_empty() {
+            tokens.push(format!("{{ {comment} }}"));
+            needs_number = true;
+        }
+        for variation in &pgn_move.variations {
+            tokens.push("(".to_owned());
+            write_line(tokens, variation, position.clone());
+            tokens.push(")".to_owned());
+            needs_number = true;
+        }
+
+        position.play(pgn_move.mv).expect("the moves of a game should be legal");
+    }
+}
+
+/// Parses a line of moves (the mainline, or a variation if `in_variation` is set) that
+/// starts from the given position, along with the comment that precedes it. The result
+/// that ends the mainline is stored into `result`.
+fn parse_line(
+    tokens: &mut Peekable<Tokenizer>,
+    mut position: Position,
+    in_variation: bool,
+    result: &mut Option<String>,
+) -> Result<(Option<String>, Vec<PgnMove>, Position)> {
+    let mut comment = None;
+    let mut moves: Vec<PgnMove> = Vec::new();
+    // The position before the last move, from which its variations start
+    let mut previous = position.clone();
+    let mut line = 1;
+
+    loop {
+        if !in_variation && matches!(tokens.peek(), Some(Ok((Token::Tag(..), _)))) {
+            // The next game begins, without this one having a result
+            break;
+        }
+        let token = match tokens.next() {
+            Some(token) => token?,
+            None if in_variation => {
+                return Err(StockfishError::InvalidPgn { line, reason: "unterminated variation".to_owned() });
+            }
+            None => break,
+        };
+        let error = |reason: &str| StockfishError::InvalidPgn { line: token.1, reason: reason.to_owned() };
+        line = token.1;
+
+        match token.0 {
+            Token::San(text) => {
+                let mv = position.parse_move(&text).map_err(|err| error(&err.to_string()))?;
+                let pgn_move = PgnMove::new(mv, position.to_san(mv)?);
+                previous = position.clone();
+                position.play(mv)?;
+                moves.push(pgn_move);
+            }
+            Token::Nag(nag) => {
+                let last = moves.last_mut().ok_or_else(|| error("annotation glyph before any move"))?;
+                last.nags.push(nag);
+            }
+            Token::Comment(text) => match moves.last_mut() {
+                Some(last) => last.read_comment(&text),
+                None => append_comment(&mut comment, text),
+            },
+            Token::StartVariation => {
+                let last = moves.last_mut().ok_or_else(|| error("variation before any move"))?;
+                let (starting_comment, mut variation, _) = parse_line(tokens, previous.clone(), true, result)?;
+                if let Some(first) = variation.first_mut() {
+                    first.starting_comment = starting_comment;
+                }
+                last.variations.push(variation);
+            }
+            Token::EndVariation if in_variation => break,
+            Token::EndVariation => return Err(error("unmatched \")\"")),
+            Token::Result(_) if in_variation => return Err(error("result inside a variation")),
+            Token::Result(game_result) => {
+                *result = Some(game_result);
+                break;
+            }
+            Token::Tag(..) => return Err(error("tag inside a variation")),
+        }
+    }
+    Ok((comment, moves, position))
+}
+
+/// Removes the characters that can't appear in a PGN comment.
+fn clean_comment(comment: &str) -> String {
+    comment.replace('}', "").split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn append_comment(comment: &mut Option<String>, text: String) {
+    if text.is_empty() {
+        return;
+    }
+    match comment {
+        Some(comment) => {
+            comment.push(' ');
+            comment.push_str(&text);
+        }
+        None => *comment = Some(text),
+    }
+}
+
+/// A move of a [`PgnGame`], along with the annotations that followed it and the
+/// variations that were given as alternatives to it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PgnMove {
+    mv: Move,
+    san: String,
+    nags: Vec<u8>,
+    starting_comment: Option<String>,
+    comment: Option<String>,
+    eval: Option<EngineEval>,
+    clock: Option<Duration>,
+    variations: Vec<Vec<PgnMove>>,
+}
+
+impl PgnMove {
+    /// Returns the move.
+    #[must_use]
+    pub fn mv(&self) -> Move {
+        self.mv
+    }
+
+    /// Returns the move in standard algebraic notation (as generated from the position,
+    /// regardless of how it was written in the PGN.)
+    #[must_use]
+    pub fn san(&self) -> &str {
+        &self.san
+    }
+
+    /// Returns the numeric annotation glyphs of the move (e.g., `1` for `"!"` and `2` for
+    /// `"?"`), including those written as suffixes to the move.
+    #[must_use]
+    pub fn nags(&self) -> &[u8] {
+        &self.nags
+    }
+
+    /// Adds a numeric annotation glyph to the move (e.g., `1` for `"!"`, or `14` for
+    /// "White is slightly better".)
+    pub fn add_nag(&mut self, nag: u8) {
+        self.nags.push(nag);
+    }
+
+    /// Returns the comment that preceded the move, if it was the first of a variation.
+    #[must_use]
+    pub fn starting_comment(&self) -> Option<&str> {
+        self.starting_comment.as_deref()
+    }
+
+    /// Returns the comment that followed the move, if any. The `[%eval ...]` and
+    /// `[%clk ...]` commands are taken out of the comment; see [`PgnMove::eval`] and
+    /// [`PgnMove::clock`].
+    #[must_use]
+    pub fn comment(&self) -> Option<&str> {
+        self.comment.as_deref()
+    }
+
+    /// Sets the comment that follows the move.
+    pub fn set_comment(&mut self, comment: Option<&str>) {
+        self.comment = comment.map(clean_comment);
+    }
+
+    /// Returns the evaluation of the position after the move, from white's point of view
+    /// (as in [`EngineOutput::eval`](crate::EngineOutput::eval)), as given by a
+    /// `[%eval ...]` command.
+    #[must_use]
+    pub fn eval(&self) -> Option<EngineEval> {
+        self.eval
+    }
+
+    /// Sets the evaluation of the position after the move, from white's point of view.
+    pub fn set_eval(&mut self, eval: Option<EngineEval>) {
+        self.eval = eval;
+    }
+
+    /// Returns the time left on the clock of the side that played the move, as given by a
+    /// `[%clk ...]` command.
+    #[must_use]
+    pub fn clock(&self) -> Option<Duration> {
+        self.clock
+    }
+
+    /// Sets the time left on the clock of the side that played the move.
+    pub fn set_clock(&mut self, clock: Option<Duration>) {
+        self.clock = clock;
+    }
+
+    /// Returns the variations that were given as alternatives to this move, each of which
+    /// starts from the position before the move.
+    #[must_use]
+    pub fn variations(&self) -> &[Vec<PgnMove>] {
+        &self.variations
+    }
+
+    /* Private Methods */
+
+    fn new(mv: Move, san: String) -> PgnMove {
+        PgnMove {
+            mv,
+            san,
+            nags: Vec::new(),
+            starting_comment: None,
+            comment: None,
+            eval: None,
+            clock: None,
+            variations: Vec::new(),
+        }
+    }
+
+    /// Adds a comment read from PGN, taking the `[%eval ...]` and `[%clk ...]` commands out of it.
+    fn read_comment(&mut self, text: &str) {
+        let mut rest = String::new();
+        let mut remaining = text;
+        while let Some(start) = remaining.find("[%") {
+            let Some(length) = remaining[start..].find(']') else {
+                break;
+            };
+            let command = &remaining[start + 2..start + length];
+            let (name, argument) = command.split_once(' ').unwrap_or((command, ""));
+            let parsed = match name {
+                "eval" => parse_eval(argument.trim()).map(|eval| self.eval = Some(eval)),
+                "clk" => parse_clock(argument.trim()).map(|clock| self.clock = Some(clock)),
+                _ => None,
+            };
+
+            rest.push_str(&remaining[..start]);
+            if parsed.is_none() {
+                // Keep the commands that aren't understood as they are
+                rest.push_str(&remaining[start..=start + length]);
+            }
+            remaining = &remaining[start + length + 1..];
+        }
+        rest.push_str(remaining);
+        append_comment(&mut self.comment, clean_comment(&rest));
+    }
+
+    /// Returns the comment to export, including the evaluation and clock commands.
+    fn exported_comment(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(eval) = self.eval {
+            parts.push(match eval.eval_type() {
+                EvalType::Centipawn => {
+                    let sign = if eval.value() < 0 {"-"} else {""};
+                    let centipawns = eval.value().unsigned_abs();
+                    format!("[%eval {sign}{}.{:02}]", centipawns / 100, centipawns % 100)
+                }
+                EvalType::Mate => format!("[%eval #{}]", eval.value()),
+            });
+        }
+        if let Some(clock) = self.clock {
+            let seconds = clock.as_secs();
+            let tenths = clock.subsec_millis() / 100;
+            let mut time = format!("{}:{:02}:{:02}", seconds / 3600, seconds / 60 % 60, seconds % 60);
+            if tenths > 0 {
+                time.push_str(&format!(".{tenths}"));
+            }
+            parts.push(format!("[%clk {time}]"));
+        }
+        parts.extend(self.comment.clone());
+        parts.join(" ")
+    }
+}
+
+/// Parses the argument of an `[%eval ...]` command: a number of pawns (e.g., `"-0.35"`)
+/// or a mate (e.g., `"#-3"`), optionally followed by the search depth (e.g., `"0.35,20"`.)
+fn parse_eval(argument: &str) -> Option<EngineEval> {
+    let value = argument.split(',').next()?;
+    if let Some(mate) = value.strip_prefix('#') {
+        return mate.parse().ok().map(|mate| EngineEval::new(EvalType::Mate, mate));
+    }
+    let pawns: f64 = value.parse().ok()?;
+    Some(EngineEval::new(EvalType::Centipawn, (pawns * 100.0).round() as i32))
+}
+
+/// Parses the argument of a `[%clk ...]` command (e.g., `"1:05:09"` or `"0:00:04.2"`.)
+fn parse_clock(argument: &str) -> Option<Duration> {
+    let mut fields = argument.split(':').rev();
+    let seconds: f64 = fields.next()?.parse().ok()?;
+    let minutes: u64 = fields.next().map_or(Ok(0), str::parse).ok()?;
+    let hours: u64 = fields.next().map_or(Ok(0), str::parse).ok()?;
+    if fields.next().is_some() || !seconds.is_finite() || seconds < 0.0 {
+        return None;
+    }
+    Some(Duration::from_secs(hours * 3600 + minutes * 60) + Duration::from_secs_f64(seconds))
+}
+
+/// Reads the games of a PGN one at a time, e.g. from a large file, without reading
+/// the whole file into memory. A malformed game is yielded as an error in its place,
+/// and reading resumes at the next game (the next `[Event` tag or header section.)
+///
+/// # Example
+///
+/// ```no_run
+/// use std::{fs::File, io::BufReader};
+/// use stockfish::PgnReader;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let reader = PgnReader::new(BufReader::new(File::open("games.pgn")?));
+/// for game in reader {
+///     let game = game?;
+///     println!("{} - {}: {}", game.header("White").unwrap_or("?"), game.header("Black").unwrap_or("?"), game.result());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct PgnReader<R> {
+    reader: R,
+    /// The number of lines read so far.
+    line: usize,
+    /// A header line that was read past the end of the previous game.
+    next_line: Option<String>,
+    /// The games parsed ahead, and the errors of the malformed ones, in order.
+    parsed: VecDeque<Result<PgnGame>>,
+    failed: bool,
+}
+
+impl<R: BufRead> PgnReader<R> {
+
+    /// Creates a reader of the games of the given PGN.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use stockfish::PgnReader;
+    ///
+    /// let pgn = "1. e4 e5 *\n\n1. e4 Ke7 Qh5 *\n\n[Event \"Third\"]\n\n1. d4 *\n";
+    /// let results: Vec<_> = PgnReader::new(pgn.as_bytes()).collect();
+    /// assert_eq!(results.len(), 3);
+    /// assert_eq!(results[0].as_ref().unwrap().moves().len(), 2);
+    /// assert!(results[1].is_err()); // Ke7 is illegal
+    /// assert_eq!(results[2].as_ref().unwrap().header("Event"), Some("Third"));
+    /// ```
+    #[must_use]
+    pub fn new(reader: R) -> Self {
+        Self { reader, line: 0, next_line: None, parsed: VecDeque::new(), failed: false }
+    }
+
+    /* Private Methods */
+
+    /// Reads the lines of the next game, along with the number of its first line.
+    /// A game ends where the header section of the next one begins.
+    fn read_game_text(&mut self) -> Result<Option<(String, usize)>> {
+        let mut text = String::new();
+        let mut first_line = self.line;
+        let mut in_movetext = false;
+        let mut in_comment = false;
+
+        if let Some(line) = self.next_line.take() {
+            text.push_str(&line);
+        } else {
+            first_line += 1;
+        }
+        loop {
+            let mut line = String::new();
+            if self.reader.read_line(&mut line)? == 0 {
+                break;
+            }
+            self.line += 1;
+
+            let trimmed = line.trim_start();
+            if !in_comment && in_movetext && trimmed.starts_with('[') {
+                self.next_line = Some(line);
+                break;
+            }
+            if !in_comment && !trimmed.is_empty() && !trimmed.starts_with('[') && !trimmed.starts_with('%') {
+                in_movetext = true;
+            }
+            for char in line.chars() {
+                match char {
+                    '{' => in_comment = true,
+                    '}' => in_comment = false,
+                    ';' if !in_comment => break,
+                    _ => {}
+                }
+            }
+            text.push_str(&line);
+        }
+
+        Ok((!text.trim().is_empty()).then_some((text, first_line)))
+    }
+}
+
+impl<R: BufRead> Iterator for PgnReader<R> {
+    type Item = Result<PgnGame>;
+
+    fn next(&mut self) -> Option<Result<PgnGame>> {
+        while self.parsed.is_empty() && !self.failed {
+            let (text, first_line) = match self.read_game_text() {
+                Ok(Some(game_text)) => game_text,
+                Ok(None) => return None,
+                Err(err) => {
+                    self.failed = true;
+                    return Some(Err(err));
+                }
+            };
+
+            let mut tokens = Tokenizer::new(&text, first_line).peekable();
+            loop {
+                match PgnGame::parse_next(&mut tokens) {
+                    Ok(Some(game)) => self.parsed.push_back(Ok(game)),
+                    Ok(None) => break,
+                    Err(err) => {
+                        // Skip the rest of the malformed game, up to the next one's tags
+                        self.parsed.push_back(Err(err));
+                        let is_event = |token: &Result<(Token, usize)>| {
+                            matches!(token, Ok((Token::Tag(name, _), _)) if name == "Event")
+                        };
+                        while tokens.next_if(|token| !is_event(token)).is_some() {}
+                    }
+                }
+            }
+        }
+        self.parsed.pop_front()
+    }
+}
+
+/// A token of PGN, apart from move numbers and escaped lines, which are skipped.
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Tag(String, String),
+    Comment(String),
+    Nag(u8),
+    StartVariation,
+    EndVariation,
+    Result(String),
+    San(String),
+}
+
+/// Splits PGN into tokens, each paired with the number of the line on which it starts.
+struct Tokenizer {
+    chars: Peekable<vec::IntoIter<char>>,
+    line: usize,
+    at_line_start: bool,
+    /// Annotation glyphs that were written as a suffix to the previous move.
+    pending: VecDeque<u8>,
+}
+
+impl Tokenizer {
+    fn new(text: &str, first_line: usize) -> Tokenizer {
+        Tokenizer {
+            chars: text.chars().collect::<Vec<_>>().into_iter().peekable(),
+            line: first_line,
+            at_line_start: true,
+            pending: VecDeque::new(),
+        }
+    }
+
+    fn advance(&mut self) -> Option<char> {
+        let char = self.chars.next()?;
+        self.at_line_start = char == '\n';
+        if char == '\n' {
+            self.line += 1;
+        }
+        Some(char)
+    }
+
+    /// Reads characters up to (and including) the given delimiter, or returns [`None`]
+    /// if the text ends first.
+    fn read_until(&mut self, delimiter: char) -> Option<String> {
+        let mut text = String::new();
+        loop {
+            match self.advance()? {
+                char if char == delimiter => return Some(text),
+                char => text.push(char),
+            }
+        }
+    }
+
+    fn read_rest_of_line(&mut self) -> String {
+        let mut text = String::new();
+        while let Some(char) = self.advance() {
+            if char == '\n' {
+                break;
+            }
+            text.push(char);
+        }
+        text
+    }
+
+    fn error(&self, line: usize, reason: &str) -> StockfishError {
+        StockfishError::InvalidPgn { line, reason: reason.to_owned() }
+    }
+
+    fn read_tag(&mut self, line: usize) -> Result<Token> {
+        let content = self.read_until(']').ok_or_else(|| self.error(line, "unterminated tag"))?;
+        let content = content.trim();
+        let (name, value) = content.split_once(char::is_whitespace)
+            .ok_or_else(|| self.error(line, "expected a tag name and value"))?;
+        let value = value.trim()
+            .strip_prefix('"')
+            .and_then(|value| value.strip_suffix('"'))
+            .ok_or_else(|| self.error(line, "expected the tag value to be quoted"))?;
+        let value = value.replace("\\\"", "\"").replace("\\\\", "\\");
+        Ok(Token::Tag(name.to_owned(), value))
+    }
+
+    /// Reads the rest of a move, result or annotation symbol that starts with the given text.
+    fn read_symbol(&mut self, mut symbol: String) -> Token {
+        while let Some(&char) = self.chars.peek() {
+            if char.is_whitespace() || "{}()[];$.".contains(char) {
+                break;
+            }
+            symbol.push(char);
+            self.advance();
+        }
+
+        match symbol.as_str() {
+            "1-0" | "0-1" | "1/2-1/2" | "*" => return Token::Result(symbol),
+            _ => {}
+        }
+        let move_text = symbol.trim_end_matches(['!', '?']);
+        let nag = match &symbol[move_text.len()..] {
+            "!" => Some(1),
+            "?" => Some(2),
+            "!!" => Some(3),
+            "??" => Some(4),
+            "!?" => Some(5),
+            "?!" => Some(6),
+            _ => None,
+        };
+        if move_text.is_empty() {
+            return nag.map_or(Token::San(symbol), Token::Nag);
+        }
+        self.pending.extend(nag);
+        Token::San(move_text.to_owned())
+    }
+}
+
+impl Iterator for Tokenizer {
+    type Item = Result<(Token, usize)>;
+
+    fn next(&mut self) -> Option<Result<(Token, usize)>> {
+        if let Some(nag) = self.pending.pop_front() {
+            return Some(Ok((Token::Nag(nag), self.line)));
+        }
+
+        loop {
+            let at_line_start = self.at_line_start;
+            let line = self.line;
+            let token = match self.advance()? {
+                '%' if at_line_start => {
+                    self.read_rest_of_line();
+                    continue;
+                }
+                ';' => Ok(Token::Comment(self.read_rest_of_line().trim().to_owned())),
+                '{' => match self.read_until('}') {
+                    Some(comment) => Ok(Token::Comment(comment.split_whitespace().collect::<Vec<_>>().join(" "))),
+                    None => Err(self.error(line, "unterminated comment")),
+                },
+                '[' => self.read_tag(line),
+                '(' => Ok(Token::StartVariation),
+                ')' => Ok(Token::EndVariation),
+                '$' => {
+                    let mut digits = String::new();
+                    while let Some(digit) = self.chars.next_if(char::is_ascii_digit) {
+                        digits.push(digit);
+                    }
+                    digits.parse().map(Token::Nag).map_err(|_| self.error(line, "expected a number after \"$\""))
+                }
+                char if char.is_ascii_digit() => {
+                    let mut number = String::from(char);
+                    while let Some(digit) = self.chars.next_if(char::is_ascii_digit) {
+                        number.push(digit);
+                    }
+                    if self.chars.peek() == Some(&'.') {
+                        // A move number
+                        while self.chars.next_if_eq(&'.').is_some() {}
+                        continue;
+                    }
+                    // e.g., a result, or castling written with zeros
+                    Ok(self.read_symbol(number))
+                }
+                '.' => continue,
+                char if char.is_whitespace() => continue,
+                char => Ok(self.read_symbol(String::from(char))),
+            };
+            return Some(token.map(|token| (token, line)));
+        }
+    }
+}
@@ -0,0 +1,1002 @@
+use std::{fmt, str::FromStr};
+
+use crate::error::{Result, StockfishError};
+
+/// One of the two sides of a chess game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Color {
+    White,
+    Black,
+}
+
+impl Color {
+    /// Returns the other side.
+    #[must_use]
+    pub fn opposite(self) -> Color {
+        match self {
+            Color::White => Color::Black,
+            Color::Black => Color::White,
+        }
+    }
+
+    /// The rank (counted from 0) on which the side's pieces start.
+    fn back_rank(self) -> u8 {
+        match self {
+            Color::White => 0,
+            Color::Black => 7,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Color::White => "white",
+            Color::Black => "black",
+        }
+    }
+
+    /// The direction in which the side's pawns advance, in ranks.
+    fn forward(self) -> i8 {
+        match self {
+            Color::White => 1,
+            Color::Black => -1,
+        }
+    }
+}
+
+/// The kind of a chess piece, regardless of its color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PieceKind {
+    Pawn,
+    Knight,
+    Bishop,
+    Rook,
+    Queen,
+    King,
+}
+
+impl PieceKind {
+    /// The kinds of piece that a pawn may promote to.
+    pub(crate) const PROMOTIONS: [PieceKind; 4] = [PieceKind::Queen, PieceKind::Rook, PieceKind::Bishop, PieceKind::Knight];
+
+    /// Returns the kind of piece denoted by the given (lowercase) letter.
+    pub(crate) fn from_char(char: char) -> Option<PieceKind> {
+        match char {
+            'p' => Some(PieceKind::Pawn),
+            'n' => Some(PieceKind::Knight),
+            'b' => Some(PieceKind::Bishop),
+            'r' => Some(PieceKind::Rook),
+            'q' => Some(PieceKind::Queen),
+            'k' => Some(PieceKind::King),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            PieceKind::Pawn => "pawn",
+            PieceKind::Knight => "knight",
+            PieceKind::Bishop => "bishop",
+            PieceKind::Rook => "rook",
+            PieceKind::Queen => "queen",
+            PieceKind::King => "king",
+        }
+    }
+
+    /// Returns the lowercase letter denoting the kind of piece.
+    pub(crate) fn char(self) -> char {
+        match self {
+            PieceKind::Pawn => 'p',
+            PieceKind::Knight => 'n',
+            PieceKind::Bishop => 'b',
+            PieceKind::Rook => 'r',
+            PieceKind::Queen => 'q',
+            PieceKind::King => 'k',
+        }
+    }
+}
+
+/// A chess piece of a given color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Piece {
+    color: Color,
+    kind: PieceKind,
+}
+
+impl Piece {
+
+    #[must_use]
+    pub fn new(color: Color, kind: PieceKind) -> Self {
+        Self { color, kind }
+    }
+
+    #[must_use]
+    pub fn color(&self) -> Color {
+        self.color
+    }
+
+    #[must_use]
+    pub fn kind(&self) -> PieceKind {
+        self.kind
+    }
+
+    /// Returns the piece denoted by the given FEN letter (uppercase for white.)
+    fn from_char(char: char) -> Option<Piece> {
+        let kind = PieceKind::from_char(char.to_ascii_lowercase())?;
+        let color = if char.is_ascii_uppercase() {Color::White} else {Color::Black};
+        Some(Piece { color, kind })
+    }
+
+    /// Returns the FEN letter denoting the piece (uppercase for white.)
+    fn char(self) -> char {
+        match self.color {
+            Color::White => self.kind.char().to_ascii_uppercase(),
+            Color::Black => self.kind.char(),
+        }
+    }
+}
+
+/// A square of the board, e.g. `e4`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Square(u8);
+
+impl Square {
+
+    /// Parses the name of a square, e.g. `"e4"`.
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Square> {
+        let &[file, rank] = name.as_bytes() else {
+            return None;
+        };
+        if !(b'a'..=b'h').contains(&file) || !(b'1'..=b'8').contains(&rank) {
+            return None;
+        }
+        Some(Square::new(file - b'a', rank - b'1'))
+    }
+
+    /// Returns the file of the square, from 0 (the a-file) to 7 (the h-file).
+    #[must_use]
+    pub fn file(self) -> u8 {
+        self.0 % 8
+    }
+
+    /// Returns the rank of the square, from 0 (the 1st rank) to 7 (the 8th rank).
+    #[must_use]
+    pub fn rank(self) -> u8 {
+        self.0 / 8
+    }
+
+    /// The square at the given file and rank, both counted from 0.
+    pub(crate) fn new(file: u8, rank: u8) -> Square {
+        Square(rank * 8 + file)
+    }
+
+    /// The index of the square, from 0 (a1) to 63 (h8), rank by rank.
+    pub(crate) fn index(self) -> usize {
+        usize::from(self.0)
+    }
+
+    /// Returns the square the given number of files and ranks away, if it is on the board.
+    fn offset(self, files: i8, ranks: i8) -> Option<Square> {
+        let file = self.file().checked_add_signed(files).filter(|&file| file < 8)?;
+        let rank = self.rank().checked_add_signed(ranks).filter(|&rank| rank < 8)?;
+        Some(Square::new(file, rank))
+    }
+}
+impl fmt::Display for Square {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", char::from(b'a' + self.file()), char::from(b'1' + self.rank()))
+    }
+}
+
+/// A move, which is written in long UCI algebraic notation, e.g. `"e2e4"` or `"e7e8q"`.
+/// Castling is written as the king's move, e.g. `"e1g1"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Move {
+    from: Square,
+    to: Square,
+    promotion: Option<PieceKind>,
+}
+
+impl Move {
+
+    #[must_use]
+    pub fn new(from: Square, to: Square, promotion: Option<PieceKind>) -> Self {
+        Self { from, to, promotion }
+    }
+
+    /// Parses a move in long UCI algebraic notation.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StockfishError::IllegalMove`] if the move is malformed.
+    pub fn parse(move_str: &str) -> Result<Move> {
+        Move::parse_uci(move_str).ok_or_else(|| StockfishError::IllegalMove {
+            move_str: move_str.to_owned(),
+            ply: None,
+            reason: "expected a move in long algebraic notation, e.g. \"e2e4\"".to_owned(),
+        })
+    }
+
+    #[must_use]
+    pub fn from(&self) -> Square {
+        self.from
+    }
+
+    #[must_use]
+    pub fn to(&self) -> Square {
+        self.to
+    }
+
+    /// Returns the kind of piece that a pawn is promoted to by this move, if any.
+    #[must_use]
+    pub fn promotion(&self) -> Option<PieceKind> {
+        self.promotion
+    }
+
+    fn parse_uci(move_str: &str) -> Option<Move> {
+        let from = Square::from_name(move_str.get(0..2)?)?;
+        let to = Square::from_name(move_str.get(2..4)?)?;
+        let promotion = match move_str.get(4..)? {
+            "" => None,
+            promotion => {
+                let mut chars = promotion.chars();
+                let kind = chars.next().and_then(PieceKind::from_char)?;
+                if chars.next().is_some() || !PieceKind::PROMOTIONS.contains(&kind) {
+                    return None;
+                }
+                Some(kind)
+            }
+        };
+        Some(Move { from, to, promotion })
+    }
+}
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", self.from, self.to)?;
+        match self.promotion {
+            Some(kind) => write!(f, "{}", kind.char()),
+            None => Ok(()),
+        }
+    }
+}
+impl FromStr for Move {
+    type Err = StockfishError;
+
+    fn from_str(str: &str) -> Result<Self> {
+        Move::parse(str)
+    }
+}
+
+/// The side of the board towards which a king castles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CastlingSide {
+    /// Castling with the rook on the h-file (`O-O`).
+    KingSide,
+    /// Castling with the rook on the a-file (`O-O-O`).
+    QueenSide,
+}
+
+impl CastlingSide {
+    /// The file (counted from 0) on which the side's rook starts.
+    fn rook_file(self) -> u8 {
+        match self {
+            CastlingSide::KingSide => 7,
+            CastlingSide::QueenSide => 0,
+        }
+    }
+}
+
+/// The castling rights of a position, indexed by [`CastlingRights::index`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+struct CastlingRights([bool; 4]);
+
+impl CastlingRights {
+    const FEN_CHARS: [char; 4] = ['K', 'Q', 'k', 'q'];
+
+    fn index(color: Color, side: CastlingSide) -> usize {
+        match (color, side) {
+            (Color::White, CastlingSide::KingSide) => 0,
+            (Color::White, CastlingSide::QueenSide) => 1,
+            (Color::Black, CastlingSide::KingSide) => 2,
+            (Color::Black, CastlingSide::QueenSide) => 3,
+        }
+    }
+
+    fn all() -> [(Color, CastlingSide); 4] {
+        [
+            (Color::White, CastlingSide::KingSide),
+            (Color::White, CastlingSide::QueenSide),
+            (Color::Black, CastlingSide::KingSide),
+            (Color::Black, CastlingSide::QueenSide),
+        ]
+    }
+}
+
+const KNIGHT_OFFSETS: [(i8, i8); 8] = [(1, 2), (2, 1), (2, -1), (1, -2), (-1, -2), (-2, -1), (-2, 1), (-1, 2)];
+const KING_OFFSETS: [(i8, i8); 8] = [(0, 1), (1, 1), (1, 0), (1, -1), (0, -1), (-1, -1), (-1, 0), (-1, 1)];
+const ROOK_DIRECTIONS: [(i8, i8); 4] = [(0, 1), (1, 0), (0, -1), (-1, 0)];
+const BISHOP_DIRECTIONS: [(i8, i8); 4] = [(1, 1), (1, -1), (-1, -1), (-1, 1)];
+
+/// The FEN of the standard starting position.
+pub(crate) const STARTING_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+/// A chess position: the placement of the pieces, the side to move, the castling
+/// rights, the en passant square, and the move counters.
+///
+/// A [`Position`] only ever holds a legal position; FENs are validated when they are
+/// parsed, and only legal moves may be played. [`Stockfish`](crate::Stockfish) keeps one
+/// in sync with the engine's position, which is available through
+/// [`Stockfish::get_position`](crate::Stockfish::get_position).
+///
+/// # Example
+///
+/// ```rust
+/// use stockfish::Position;
+/// # fn main() -> Result<(), stockfish::StockfishError> {
+/// let mut position = Position::starting();
+/// assert_eq!(position.legal_moves().len(), 20);
+///
+/// for move_str in ["f2f3", "e7e5", "g2g4", "d8h4"] {
+///     position.play_uci(move_str)?;
+/// }
+/// assert!(position.is_checkmate());
+/// assert_eq!(position.to_fen(), "rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3");
+///
+/// assert!(Position::from_fen("8/8/8/8/8/8/8/8 w - - 0 1").is_err());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Position {
+    board: [Option<Piece>; 64],
+    side_to_move: Color,
+    castling: CastlingRights,
+    /// The square passed over by a pawn's double step on the last move, even if no
+    /// pawn can capture on it.
+    en_passant: Option<Square>,
+    halfmove_clock: u32,
+    fullmove_number: u32,
+}
+
+impl Position {
+
+    /// Returns the standard starting position.
+    #[must_use]
+    pub fn starting() -> Position {
+        Position::from_fen(STARTING_FEN).expect("the starting position should be valid")
+    }
+
+    /// Parses and validates a position from a FEN. The castling, en passant, and move
+    /// counter fields may be omitted.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StockfishError::IllegalPosition`] if the FEN is malformed, or if it
+    /// describes a position that can't arise in a game: e.g., one without exactly one king
+    /// per side, with too many pieces, with pawns on the first or last rank, with castling
+    /// rights or an en passant square that don't match the pieces, or in which the side
+    /// not to move is in check.
+    pub fn from_fen(fen: &str) -> Result<Position> {
+        let position = Position::parse_fen(fen)?;
+        position.validate().map_err(|reason| StockfishError::illegal_position(fen, reason))?;
+        Ok(position)
+    }
+
+    /// Returns the piece standing on the given square, if any.
+    #[must_use]
+    pub fn piece_at(&self, square: Square) -> Option<Piece> {
+        self.board[square.index()]
+    }
+
+    /// Returns the side whose turn it is to move.
+    #[must_use]
+    pub fn side_to_move(&self) -> Color {
+        self.side_to_move
+    }
+
+    /// Returns `true` if the given side may still castle towards the given side of the board.
+    /// (This doesn't take into account whether castling is possible in this very position.)
+    #[must_use]
+    pub fn has_castling_right(&self, color: Color, side: CastlingSide) -> bool {
+        self.castling.0[CastlingRights::index(color, side)]
+    }
+
+    /// Returns the square on which a pawn may be captured en passant, if any. (As in the
+    /// FENs that Stockfish outputs, there is none unless a pawn can legally capture on it.)
+    #[must_use]
+    pub fn en_passant_square(&self) -> Option<Square> {
+        self.en_passant.filter(|&square| self.legal_moves().iter().any(|mv| {
+            mv.to == square && self.piece_at(mv.from).is_some_and(|piece| piece.kind == PieceKind::Pawn)
+        }))
+    }
+
+    /// Returns the number of plies since the last capture or pawn move.
+    #[must_use]
+    pub fn halfmove_clock(&self) -> u32 {
+        self.halfmove_clock
+    }
+
+    /// Returns the number of the current move, starting from 1 and incremented
+    /// after each of black's moves.
+    #[must_use]
+    pub fn fullmove_number(&self) -> u32 {
+        self.fullmove_number
+    }
+
+    /// Returns every legal move in the position.
+    #[must_use]
+    pub fn legal_moves(&self) -> Vec<Move> {
+        let mut moves = self.pseudo_legal_moves();
+        moves.retain(|&mv| {
+            let mut position = self.clone();
+            position.apply(mv);
+            !position.is_king_attacked(self.side_to_move)
+        });
+        moves.extend(self.castling_moves());
+        moves
+    }
+
+    /// Returns `true` if the given move is legal in the position.
+    #[must_use]
+    pub fn is_legal(&self, mv: Move) -> bool {
+        self.legal_moves().contains(&mv)
+    }
+
+    /// Returns `true` if the side to move is in check.
+    #[must_use]
+    pub fn is_check(&self) -> bool {
+        self.is_king_attacked(self.side_to_move)
+    }
+
+    /// Returns `true` if the side to move is checkmated.
+    #[must_use]
+    pub fn is_checkmate(&self) -> bool {
+        self.is_check() && self.legal_moves().is_empty()
+    }
+
+    /// Returns `true` if the side to move is stalemated.
+    #[must_use]
+    pub fn is_stalemate(&self) -> bool {
+        !self.is_check() && self.legal_moves().is_empty()
+    }
+
+    /// Returns `true` if neither side has enough material left to checkmate: only the
+    /// kings remain, along with at most one knight or any number of bishops that all
+    /// stand on squares of the same color.
+    #[must_use]
+    pub fn is_insufficient_material(&self) -> bool {
+        let pieces: Vec<(Square, Piece)> = (0..64)
+            .filter_map(|index| self.board[usize::from(index)].map(|piece| (Square(index), piece)))
+            .filter(|(_, piece)| piece.kind != PieceKind::King)
+            .collect();
+        match pieces.as_slice() {
+            [] | [(_, Piece { kind: PieceKind::Knight, .. })] => true,
+            [(square, _), ..] => {
+                let square_color = |square: &Square| (square.file() + square.rank()) % 2;
+                pieces.iter().all(|(other, piece)| {
+                    piece.kind == PieceKind::Bishop && square_color(other) == square_color(square)
+                })
+            }
+        }
+    }
+
+    /// Returns the number of the ply that is to be played next, counted from 1 for
+    /// white's first move (as given by the fullmove number.)
+    #[must_use]
+    pub fn ply(&self) -> u32 {
+        let black_to_move = u32::from(self.side_to_move == Color::Black);
+        (self.fullmove_number - 1) * 2 + black_to_move + 1
+    }
+
+    /// Plays the given move on the position.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use stockfish::{Position, StockfishError};
+    ///
+    /// let mut position = Position::starting();
+    /// let Err(StockfishError::IllegalMove { ply, reason, .. }) = position.play_uci("e1g1") else {
+    ///     panic!("castling through pieces should be illegal");
+    /// };
+    /// assert_eq!(ply, Some(1));
+    /// assert_eq!(reason, "white can't castle king-side: there are pieces between the king and the rook");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StockfishError::IllegalMove`] if the move isn't legal in the position,
+    /// in which case the position is left unchanged. The error describes why the move
+    /// is illegal.
+    pub fn play(&mut self, mv: Move) -> Result<()> {
+        if !self.is_legal(mv) {
+            return Err(StockfishError::IllegalMove {
+                move_str: mv.to_string(),
+                ply: Some(self.ply()),
+                reason: self.explain_illegal(mv),
+            });
+        }
+        self.apply(mv);
+        Ok(())
+    }
+
+    /// Plays the given move, in long UCI algebraic notation, on the position.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StockfishError::IllegalMove`] if the move is malformed or isn't legal in
+    /// the position, in which case the position is left unchanged.
+    pub fn play_uci(&mut self, move_str: &str) -> Result<()> {
+        let mv = Move::parse(move_str).map_err(|err| match err {
+            StockfishError::IllegalMove { move_str, reason, .. } => {
+                StockfishError::IllegalMove { move_str, ply: Some(self.ply()), reason }
+            }
+            err => err,
+        })?;
+        self.play(mv)
+    }
+
+    /// Serializes the position into a FEN.
+    #[must_use]
+    pub fn to_fen(&self) -> String {
+        self.to_string()
+    }
+
+    /* Private Methods */
+
+    fn parse_fen(fen: &str) -> Result<Position> {
+        let fields: Vec<&str> = fen.split_whitespace().collect();
+        if fields.len() < 2 || fields.len() > 6 {
+            return Err(StockfishError::illegal_position(fen, "expected between 2 and 6 fields"));
+        }
+
+        let ranks: Vec<&str> = fields[0].split('/').collect();
+        if ranks.len() != 8 {
+            return Err(StockfishError::illegal_position(fen, "expected 8 ranks"));
+        }
+        let mut board = [None; 64];
+        for (rank_index, rank) in ranks.into_iter().enumerate() {
+            // FENs list the ranks from the 8th down to the 1st
+            let rank_number = 7 - rank_index as u8;
+            let mut file = 0;
+            for char in rank.chars() {
+                if let Some(empty) = char.to_digit(10).filter(|digit| (1..=8).contains(digit)) {
+                    file += empty;
+                    continue;
+                }
+                let piece = Piece::from_char(char)
+                    .ok_or_else(|| StockfishError::illegal_position(fen, "invalid character in piece placement"))?;
+                if file < 8 {
+                    board[Square::new(file as u8, rank_number).index()] = Some(piece);
+                }
+                file += 1;
+            }
+            if file != 8 {
+                return Err(StockfishError::illegal_position(fen, "each rank should span 8 files"));
+            }
+        }
+
+        let side_to_move = match fields[1] {
+            "w" => Color::White,
+            "b" => Color::Black,
+            _ => return Err(StockfishError::illegal_position(fen, "side to move should be \"w\" or \"b\"")),
+        };
+
+        let mut castling = CastlingRights::default();
+        let castling_field = fields.get(2).copied().unwrap_or("-");
+        if castling_field != "-" {
+            for char in castling_field.chars() {
+                let index = CastlingRights::FEN_CHARS.iter().position(|&right| right == char)
+                    .ok_or_else(|| StockfishError::illegal_position(fen, "invalid castling rights"))?;
+                castling.0[index] = true;
+            }
+        }
+
+        let en_passant = match fields.get(3).copied().unwrap_or("-") {
+            "-" => None,
+            name => Some(Square::from_name(name)
+                .ok_or_else(|| StockfishError::illegal_position(fen, "invalid en passant square"))?),
+        };
+
+        let parse_counter = |index: usize, default: u32, field: &str| -> Result<u32> {
+            fields.get(index).map_or(Ok(default), |value| value.parse()
+                .map_err(|_| StockfishError::illegal_position(fen, &format!("invalid {field}"))))
+        };
+        let halfmove_clock = parse_counter(4, 0, "halfmove clock")?;
+        let fullmove_number = parse_counter(5, 1, "fullmove number")?.max(1);
+
+        Ok(Position { board, side_to_move, castling, en_passant, halfmove_clock, fullmove_number })
+    }
+
+    /// Checks that the position could arise in a game, returning the reason if it couldn't.
+    fn validate(&self) -> std::result::Result<(), &'static str> {
+        for color in [Color::White, Color::Black] {
+            let count = |kind: PieceKind| self.board.iter()
+                .filter(|&&piece| piece == Some(Piece::new(color, kind)))
+                .count();
+
+            if count(PieceKind::King) != 1 {
+                return Err("each side should have exactly one king");
+            }
+            if self.board.iter().flatten().filter(|piece| piece.color == color).count() > 16 {
+                return Err("a side can't have more than 16 pieces");
+            }
+            // Every piece beyond those of the starting position must have been a pawn
+            let promoted = count(PieceKind::Queen).saturating_sub(1)
+                + count(PieceKind::Rook).saturating_sub(2)
+                + count(PieceKind::Bishop).saturating_sub(2)
+                + count(PieceKind::Knight).saturating_sub(2);
+            if count(PieceKind::Pawn) + promoted > 8 {
+                return Err("a side can't have more than 8 pawns, including promoted ones");
+            }
+        }
+
+        let pawn_on_back_rank = (0..8).flat_map(|file| [Square::new(file, 0), Square::new(file, 7)])
+            .any(|square| self.piece_at(square).is_some_and(|piece| piece.kind == PieceKind::Pawn));
+        if pawn_on_back_rank {
+            return Err("pawns can't stand on the first or last rank");
+        }
+
+        for (color, side) in CastlingRights::all() {
+            if !self.has_castling_right(color, side) {
+                continue;
+            }
+            let rank = color.back_rank();
+            let king = self.piece_at(Square::new(4, rank));
+            let rook = self.piece_at(Square::new(side.rook_file(), rank));
+            if king != Some(Piece::new(color, PieceKind::King)) || rook != Some(Piece::new(color, PieceKind::Rook)) {
+                return Err("castling rights require the king and rook on their original squares");
+            }
+        }
+
+        if let Some(square) = self.en_passant {
+            // The pawn that just advanced two squares stands in front of the en passant square
+            let mover = self.side_to_move.opposite();
+            let expected_rank = if mover == Color::White {2} else {5};
+            let pushed = square.offset(0, mover.forward());
+            let origin = square.offset(0, -mover.forward());
+            if square.rank() != expected_rank
+                || self.piece_at(square).is_some()
+                || origin.and_then(|origin| self.piece_at(origin)).is_some()
+                || pushed.and_then(|pushed| self.piece_at(pushed)) != Some(Piece::new(mover, PieceKind::Pawn))
+            {
+                return Err("the en passant square doesn't follow a pawn's double step");
+            }
+        }
+
+        if self.is_king_attacked(self.side_to_move.opposite()) {
+            return Err("the side not to move is in check");
+        }
+        Ok(())
+    }
+
+    fn king_square(&self, color: Color) -> Option<Square> {
+        (0..64).map(Square).find(|&square| self.piece_at(square) == Some(Piece::new(color, PieceKind::King)))
+    }
+
+    fn is_king_attacked(&self, color: Color) -> bool {
+        self.king_square(color).is_some_and(|square| self.is_attacked(square, color.opposite()))
+    }
+
+    /// Returns `true` if any piece of the given side attacks the given square.
+    fn is_attacked(&self, square: Square, by: Color) -> bool {
+        let attacker_at = |target: Option<Square>, kinds: &[PieceKind]| {
+            target.and_then(|target| self.piece_at(target))
+                .is_some_and(|piece| piece.color == by && kinds.contains(&piece.kind))
+        };
+
+        // Pawns attack diagonally forwards, so they attack from diagonally behind the square
+        if [-1, 1].into_iter().any(|files| attacker_at(square.offset(files, -by.forward()), &[PieceKind::Pawn])) {
+            return true;
+        }
+        if KNIGHT_OFFSETS.iter().any(|&(files, ranks)| attacker_at(square.offset(files, ranks), &[PieceKind::Knight])) {
+            return true;
+        }
+        if KING_OFFSETS.iter().any(|&(files, ranks)| attacker_at(square.offset(files, ranks), &[PieceKind::King])) {
+            return true;
+        }
+
+        let slides = [
+            (ROOK_DIRECTIONS, [PieceKind::Rook, PieceKind::Queen]),
+            (BISHOP_DIRECTIONS, [PieceKind::Bishop, PieceKind::Queen]),
+        ];
+        for (directions, kinds) in slides {
+            for (files, ranks) in directions {
+                let mut current = square.offset(files, ranks);
+                while let Some(target) = current {
+                    if let Some(piece) = self.piece_at(target) {
+                        if piece.color == by && kinds.contains(&piece.kind) {
+                            return true;
+                        }
+                        break;
+                    }
+                    current = target.offset(files, ranks);
+                }
+            }
+        }
+        false
+    }
+
+    /// Returns the moves that follow the pieces' movement rules, regardless of whether they
+    /// leave the king in check. (Castling moves are generated separately.)
+    fn pseudo_legal_moves(&self) -> Vec<Move> {
+        let mut moves = Vec::new();
+        let color = self.side_to_move;
+
+        for from in (0..64).map(Square) {
+            let Some(piece) = self.piece_at(from).filter(|piece| piece.color == color) else {
+                continue;
+            };
+            if piece.kind == PieceKind::Pawn {
+                self.pawn_moves(from, &mut moves);
+                continue;
+            }
+
+            let (directions, slides): (&[(i8, i8)], bool) = match piece.kind {
+                PieceKind::Knight => (&KNIGHT_OFFSETS, false),
+                PieceKind::King => (&KING_OFFSETS, false),
+                PieceKind::Bishop => (&BISHOP_DIRECTIONS, true),
+                PieceKind::Rook => (&ROOK_DIRECTIONS, true),
+                _ => (&KING_OFFSETS, true),
+            };
+            for &(files, ranks) in directions {
+                let mut current = from.offset(files, ranks);
+                while let Some(to) = current {
+                    let target = self.piece_at(to);
+                    if target.is_none_or(|target| target.color != color) {
+                        moves.push(Move::new(from, to, None));
+                    }
+                    if target.is_some() || !slides {
+                        break;
+                    }
+                    current = to.offset(files, ranks);
+                }
+            }
+        }
+        moves
+    }
+
+    fn pawn_moves(&self, from: Square, moves: &mut Vec<Move>) {
+        let color = self.side_to_move;
+        let forward = color.forward();
+        let promotion_rank = color.opposite().back_rank();
+
+        let mut push_pawn_move = |to: Square| {
+            if to.rank() == promotion_rank {
+                moves.extend(PieceKind::PROMOTIONS.map(|kind| Move::new(from, to, Some(kind))));
+            } else {
+                moves.push(Move::new(from, to, None));
+            }
+        };
+
+        if let Some(to) = from.offset(0, forward).filter(|&to| self.piece_at(to).is_none()) {
+            push_pawn_move(to);
+            // Pawns may advance two squares from their starting rank
+            let start_rank = color.back_rank().saturating_add_signed(forward);
+            if from.rank() == start_rank
+                && let Some(to) = to.offset(0, forward).filter(|&to| self.piece_at(to).is_none())
+            {
+                push_pawn_move(to);
+            }
+        }
+        for files in [-1, 1] {
+            let Some(to) = from.offset(files, forward) else {
+                continue;
+            };
+            let captures = self.piece_at(to).is_some_and(|target| target.color != color);
+            if captures || Some(to) == self.en_passant {
+                push_pawn_move(to);
+            }
+        }
+    }
+
+    /// Returns the legal castling moves, written as the king's move.
+    fn castling_moves(&self) -> Vec<Move> {
+        let color = self.side_to_move;
+        let rank = color.back_rank();
+        if self.is_check() {
+            return Vec::new();
+        }
+
+        [CastlingSide::KingSide, CastlingSide::QueenSide].into_iter()
+            .filter(|&side| self.has_castling_right(color, side))
+            .filter_map(|side| {
+                let (between, passed, king_to): (&[u8], [u8; 2], u8) = match side {
+                    CastlingSide::KingSide => (&[5, 6], [5, 6], 6),
+                    CastlingSide::QueenSide => (&[1, 2, 3], [3, 2], 2),
+                };
+                let path_is_clear = between.iter().all(|&file| self.piece_at(Square::new(file, rank)).is_none());
+                let path_is_safe = passed.iter().all(|&file| !self.is_attacked(Square::new(file, rank), color.opposite()));
+                (path_is_clear && path_is_safe).then(|| Move::new(Square::new(4, rank), Square::new(king_to, rank), None))
+            })
+            .collect()
+    }
+
+    /// Plays a move without checking its legality.
+    fn apply(&mut self, mv: Move) {
+        let Move { from, to, promotion } = mv;
+        let Some(piece) = self.board[from.index()] else {
+            return;
+        };
+
+        let is_capture = self.board[to.index()].is_some();
+        let is_pawn = piece.kind == PieceKind::Pawn;
+
+        if is_pawn && Some(to) == self.en_passant && from.file() != to.file() {
+            // The captured pawn stands beside the moving pawn, not on the destination square
+            self.board[Square::new(to.file(), from.rank()).index()] = None;
+        }
+        if piece.kind == PieceKind::King && from.file().abs_diff(to.file()) == 2 {
+            let (rook_from, rook_to) = if to.file() > from.file() {(7, 5)} else {(0, 3)};
+            let rook = self.board[Square::new(rook_from, from.rank()).index()].take();
+            self.board[Square::new(rook_to, from.rank()).index()] = rook;
+        }
+
+        self.board[from.index()] = None;
+        self.board[to.index()] = Some(match promotion {
+            Some(kind) if is_pawn => Piece::new(piece.color, kind),
+            _ => piece,
+        });
+
+        if piece.kind == PieceKind::King {
+            self.castling.0[CastlingRights::index(piece.color, CastlingSide::KingSide)] = false;
+            self.castling.0[CastlingRights::index(piece.color, CastlingSide::QueenSide)] = false;
+        }
+        // A rook leaving or being captured on its original square loses its castling right
+        for (color, side) in CastlingRights::all() {
+            let rook_square = Square::new(side.rook_file(), color.back_rank());
+            if from == rook_square || to == rook_square {
+                self.castling.0[CastlingRights::index(color, side)] = false;
+            }
+        }
+
+        self.en_passant = None;
+        if is_pawn && from.rank().abs_diff(to.rank()) == 2 {
+            self.en_passant = Some(Square::new(from.file(), (from.rank() + to.rank()) / 2));
+        }
+
+        if is_pawn || is_capture {
+            self.halfmove_clock = 0;
+        } else {
+            self.halfmove_clock += 1;
+        }
+        if self.side_to_move == Color::Black {
+            self.fullmove_number += 1;
+        }
+        self.side_to_move = self.side_to_move.opposite();
+    }
+
+    /// Describes why the given move, which isn't legal, can't be played.
+    fn explain_illegal(&self, mv: Move) -> String {
+        let Move { from, to, promotion } = mv;
+        let color = self.side_to_move;
+        let Some(piece) = self.piece_at(from) else {
+            return format!("there is no piece on {from}");
+        };
+        if piece.color != color {
+            return format!(
+                "the {} on {from} belongs to {}, but it is {}'s turn",
+                piece.kind.name(),
+                piece.color.name(),
+                color.name(),
+            );
+        }
+
+        let reaches_last_rank = piece.kind == PieceKind::Pawn && to.rank() == color.opposite().back_rank();
+        match promotion {
+            Some(_) if !reaches_last_rank => return "only a pawn reaching the last rank may promote".to_owned(),
+            None if reaches_last_rank
+                && self.pseudo_legal_moves().iter().any(|mv| mv.from == from && mv.to == to) =>
+            {
+                return "a pawn reaching the last rank must promote".to_owned();
+            }
+            _ => {}
+        }
+
+        let back_rank = color.back_rank();
+        let castles = piece.kind == PieceKind::King
+            && from == Square::new(4, back_rank)
+            && to.rank() == back_rank
+            && (to.file() == 2 || to.file() == 6);
+        if castles {
+            let (side, description) = if to.file() == 6 {
+                (CastlingSide::KingSide, "king-side")
+            } else {
+                (CastlingSide::QueenSide, "queen-side")
+            };
+            let between: &[u8] = if side == CastlingSide::KingSide { &[5, 6] } else { &[1, 2, 3] };
+            let reason = if !self.has_castling_right(color, side) {
+                "the castling right has been lost"
+            } else if between.iter().any(|&file| self.piece_at(Square::new(file, back_rank)).is_some()) {
+                "there are pieces between the king and the rook"
+            } else if self.is_check() {
+                "the king is in check"
+            } else {
+                "the king would pass through or land on an attacked square"
+            };
+            return format!("{} can't castle {description}: {reason}", color.name());
+        }
+
+        if self.pseudo_legal_moves().contains(&mv) {
+            return if self.is_check() {
+                "the move doesn't get the king out of check".to_owned()
+            } else {
+                format!("the move would leave the {} king in check", color.name())
+            };
+        }
+        format!("the {} on {from} can't move to {to}", piece.kind.name())
+    }
+
+    /// Returns the square passed over by a pawn's double step on the last move, whether
+    /// or not a pawn can capture on it.
+    pub(crate) fn en_passant_target(&self) -> Option<Square> {
+        self.en_passant
+    }
+}
+impl PartialEq for Position {
+    fn eq(&self, other: &Self) -> bool {
+        // Positions are equal if their FENs are
+        self.board == other.board
+            && self.side_to_move == other.side_to_move
+            && self.castling == other.castling
+            && self.en_passant_square() == other.en_passant_square()
+            && self.halfmove_clock == other.halfmove_clock
+            && self.fullmove_number == other.fullmove_number
+    }
+}
+impl Eq for Position {}
+impl Default for Position {
+    fn default() -> Self {
+        Position::starting()
+    }
+}
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for rank in (0..8).rev() {
+            let mut empty = 0;
+            for file in 0..8 {
+                match self.board[Square::new(file, rank).index()] {
+                    Some(piece) => {
+                        if empty > 0 {
+                            write!(f, "{empty}")?;
+                            empty = 0;
+                        }
+                        write!(f, "{}", piece.char())?;
+                    }
+                    None => empty += 1,
+                }
+            }
+            if empty > 0 {
+                write!(f, "{empty}")?;
+            }
+            if rank > 0 {
+                write!(f, "/")?;
+            }
+        }
+
+        let side_to_move = match self.side_to_move {
+            Color::White => 'w',
+            Color::Black => 'b',
+        };
+        write!(f, " {side_to_move} ")?;
+
+        let castling: String = CastlingRights::FEN_CHARS.iter()
+            .zip(self.castling.0)
+            .filter_map(|(&char, allowed)| allowed.then_some(char))
+            .collect();
+        write!(f, "{} ", if castling.is_empty() {"-"} else {&castling})?;
+
+        match self.en_passant_square() {
+            Some(square) => write!(f, "{square}")?,
+            None => write!(f, "-")?,
+        }
+        write!(f, " {} {}", self.halfmove_clock, self.fullmove_number)
+    }
+}
+impl FromStr for Position {
+    type Err = StockfishError;
+
+    fn from_str(str: &str) -> Result<Self> {
+        Position::from_fen(str)
+    }
+}
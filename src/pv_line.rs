@@ -0,0 +1,72 @@
+use crate::engine_eval::EngineEval;
+use crate::error::Result;
+use crate::position::Position;
+use std::fmt;
+
+/// Represents one of the ranked lines returned from a MultiPV search. Contains
+/// the engine's evaluation of the line and its full principal variation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PvLine {
+    rank: u32,
+    eval: EngineEval,
+    depth: u32,
+    pv: Vec<String>,
+}
+
+impl PvLine {
+
+    #[must_use]
+    pub fn new(rank: u32, eval: EngineEval, depth: u32, pv: Vec<String>) -> Self {
+        Self { rank, eval, depth, pv }
+    }
+
+    /// Returns the rank of the line among the lines that the engine considered,
+    /// starting from 1 for the best line.
+    #[must_use]
+    pub fn rank(&self) -> u32 {
+        self.rank
+    }
+
+    /// Returns [`EngineEval`], a struct representing the engine's evaluation
+    /// of the line. As with [`EngineOutput::eval`](crate::EngineOutput::eval), the
+    /// evaluation is not relative to the player with the current move.
+    #[must_use]
+    pub fn eval(&self) -> EngineEval {
+        self.eval
+    }
+
+    /// Returns the depth to which the engine had calculated this line.
+    #[must_use]
+    pub fn depth(&self) -> u32 {
+        self.depth
+    }
+
+    /// Returns the principal variation of the line: the sequence of moves that the
+    /// engine expects to be played, in long UCI algebraic notation (e.g., `"e2e4"`.)
+    #[must_use]
+    pub fn pv(&self) -> &[String] {
+        &self.pv
+    }
+
+    /// Returns the principal variation of the line in standard algebraic notation
+    /// (e.g., `"Nf3"`), given the position that the engine searched.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StockfishError::IllegalMove`](crate::StockfishError::IllegalMove) if one
+    /// of the moves isn't legal when played from the given position.
+    pub fn pv_san(&self, position: &Position) -> Result<Vec<String>> {
+        position.line_to_san(&self.pv)
+    }
+
+    /// Returns the first move of the line, or [`None`] if the principal variation is empty.
+    #[must_use]
+    pub fn first_move(&self) -> Option<&str> {
+        self.pv.first().map(String::as_str)
+    }
+}
+impl fmt::Display for PvLine {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}. {} {}", self.rank, self.eval, self.pv.join(" "))
+    }
+}
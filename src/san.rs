@@ -0,0 +1,314 @@
+use crate::error::{Result, StockfishError};
+use crate::position::{Move, PieceKind, Position, Square};
+
+/// A move in standard algebraic notation, broken into its parts but not yet
+/// resolved against a position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SanMove {
+    Castle { king_side: bool },
+    Normal {
+        kind: PieceKind,
+        from_file: Option<u8>,
+        from_rank: Option<u8>,
+        to: Square,
+        promotion: Option<PieceKind>,
+    },
+}
+
+impl SanMove {
+    /// Parses a move in standard algebraic notation (e.g., `"Nbd7"`, `"exd8=Q+"` or
+    /// `"O-O"`.) Capture markers, check and mate suffixes, and annotations such as
+    /// `"!?"` are accepted but not required.
+    pub(crate) fn parse(san: &str) -> Option<SanMove> {
+        let san = san.trim_end_matches(['+', '#', '!', '?']);
+        match san {
+            "O-O" => return Some(SanMove::Castle { king_side: true }),
+            "O-O-O" => return Some(SanMove::Castle { king_side: false }),
+            _ => {}
+        }
+
+        let mut body = san;
+        let mut promotion = None;
+        if let Some((rest, piece)) = body.rsplit_once('=') {
+            let mut chars = piece.chars();
+            let kind = chars.next().and_then(|char| char.is_ascii_uppercase().then_some(char))
+                .and_then(|char| PieceKind::from_char(char.to_ascii_lowercase()))?;
+            if chars.next().is_some() || matches!(kind, PieceKind::Pawn | PieceKind::King) {
+                return None;
+            }
+            promotion = Some(kind);
+            body = rest;
+        }
+
+        let mut kind = PieceKind::Pawn;
+        if let Some(first) = body.chars().next().filter(char::is_ascii_uppercase) {
+            kind = PieceKind::from_char(first.to_ascii_lowercase())
+                .filter(|&kind| kind != PieceKind::Pawn)?;
+            body = &body[1..];
+        }
+
+        if body.len() < 2 || !body.is_char_boundary(body.len() - 2) {
+            return None;
+        }
+        let (disambiguation, to) = body.split_at(body.len() - 2);
+        let to = Square::from_name(to)?;
+        let disambiguation = disambiguation.strip_suffix('x').unwrap_or(disambiguation);
+
+        let (mut from_file, mut from_rank) = (None, None);
+        for char in disambiguation.chars() {
+            match char {
+                'a'..='h' if from_file.is_none() && from_rank.is_none() => from_file = Some(char as u8 - b'a'),
+                '1'..='8' if from_rank.is_none() => from_rank = Some(char as u8 - b'1'),
+                _ => return None,
+            }
+        }
+        if promotion.is_some() && kind != PieceKind::Pawn {
+            return None;
+        }
+        Some(SanMove::Normal { kind, from_file, from_rank, to, promotion })
+    }
+
+    /// Returns every legal move in the position that this move could denote.
+    pub(crate) fn candidates(self, position: &Position) -> Vec<Move> {
+        position.legal_moves().into_iter()
+            .filter(|&mv| match self {
+                SanMove::Castle { king_side } => position.castling_side(mv) == Some(king_side),
+                SanMove::Normal { kind, from_file, from_rank, to, promotion } => {
+                    position.castling_side(mv).is_none()
+                        && position.piece_at(mv.from()).is_some_and(|piece| piece.kind() == kind)
+                        && mv.to() == to
+                        && mv.promotion() == promotion
+                        && from_file.is_none_or(|file| mv.from().file() == file)
+                        && from_rank.is_none_or(|rank| mv.from().rank() == rank)
+                }
+            })
+            .collect()
+    }
+}
+
+impl Position {
+    /// Formats the given move in standard algebraic notation (e.g., `"Nf3"`, `"O-O"` or
+    /// `"exd8=Q+"`), with just enough disambiguation and a check or mate suffix.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use stockfish::{Move, Position};
+    /// # fn main() -> Result<(), stockfish::StockfishError> {
+    /// let position = Position::starting();
+    /// assert_eq!(position.to_san(Move::parse("g1f3")?)?, "Nf3");
+    /// assert_eq!(position.uci_to_san("e2e4")?, "e4");
+    /// assert_eq!(position.san_to_uci("Nc3")?, "b1c3");
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StockfishError::IllegalMove`] if the move isn't legal in the position.
+    pub fn to_san(&self, mv: Move) -> Result<String> {
+        let mut after = self.clone();
+        after.play(mv)?;
+
+        let mut san = match self.castling_side(mv) {
+            Some(true) => "O-O".to_owned(),
+            Some(false) => "O-O-O".to_owned(),
+            None => self.san_without_suffix(mv),
+        };
+        if after.is_checkmate() {
+            san.push('#');
+        } else if after.is_check() {
+            san.push('+');
+        }
+        Ok(san)
+    }
+
+    /// Converts a move from long UCI algebraic notation into standard algebraic notation.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StockfishError::IllegalMove`] if the move is malformed or isn't legal
+    /// in the position.
+    pub fn uci_to_san(&self, move_str: &str) -> Result<String> {
+        let mv = Move::parse(move_str).map_err(|err| self.at_current_ply(err))?;
+        self.to_san(mv)
+    }
+
+    /// Resolves a move in standard algebraic notation to the legal move that it denotes.
+    /// Check and mate suffixes, as well as annotations such as `"!?"`, are ignored.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use stockfish::{Position, StockfishError};
+    /// # fn main() -> Result<(), StockfishError> {
+    /// let position = Position::from_fen("4k3/8/8/8/8/8/4K3/R6R w - - 0 1")?;
+    /// assert_eq!(position.parse_san("Rad1")?.to_string(), "a1d1");
+    ///
+    /// let Err(StockfishError::AmbiguousMove { candidates, .. }) = position.parse_san("Rd1") else {
+    ///     panic!("both rooks may go to d1");
+    /// };
+    /// assert_eq!(candidates, ["Rad1", "Rhd1"]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StockfishError::AmbiguousMove`] listing the candidates if the move could
+    /// denote several legal moves, or [`StockfishError::IllegalMove`] if it is malformed, or
+    /// if it doesn't denote any legal move in the position.
+    pub fn parse_san(&self, san: &str) -> Result<Move> {
+        let Some(parsed) = SanMove::parse(san) else {
+            return Err(self.illegal_move(san, "expected a move in standard algebraic notation, e.g. \"Nf3\""));
+        };
+        match parsed.candidates(self).as_slice() {
+            [mv] => Ok(*mv),
+            [] => Err(self.illegal_move(san, "no legal move matches it")),
+            candidates => Err(self.ambiguous_move(san, candidates)),
+        }
+    }
+
+    /// Converts a move from standard algebraic notation into long UCI algebraic notation.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StockfishError::AmbiguousMove`] or [`StockfishError::IllegalMove`] if the
+    /// move doesn't denote exactly one legal move in the position (see [`Position::parse_san`].)
+    pub fn san_to_uci(&self, san: &str) -> Result<String> {
+        self.parse_san(san).map(|mv| mv.to_string())
+    }
+
+    /// Plays the given move, in standard algebraic notation, on the position.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StockfishError::AmbiguousMove`] or [`StockfishError::IllegalMove`] if the
+    /// move doesn't denote exactly one legal move in the position, in which case the position
+    /// is left unchanged.
+    pub fn play_san(&mut self, san: &str) -> Result<()> {
+        let mv = self.parse_san(san)?;
+        self.play(mv)
+    }
+
+    /// Converts a sequence of moves in long UCI algebraic notation, played one after
+    /// another from this position, into standard algebraic notation.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use stockfish::Position;
+    /// # fn main() -> Result<(), stockfish::StockfishError> {
+    /// let line = Position::starting().line_to_san(&["e2e4", "e7e5", "g1f3", "b8c6", "f1b5"])?;
+    /// assert_eq!(line, ["e4", "e5", "Nf3", "Nc6", "Bb5"]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StockfishError::IllegalMove`] if one of the moves is malformed or illegal.
+    pub fn line_to_san<S: AsRef<str>>(&self, moves: &[S]) -> Result<Vec<String>> {
+        let mut position = self.clone();
+        moves.iter()
+            .map(|move_str| {
+                let san = position.uci_to_san(move_str.as_ref())?;
+                position.play_uci(move_str.as_ref())?;
+                Ok(san)
+            })
+            .collect()
+    }
+
+    /* Private Methods */
+
+    /// Formats a legal, non-castling move in standard algebraic notation, without a
+    /// check or mate suffix.
+    fn san_without_suffix(&self, mv: Move) -> String {
+        let Some(piece) = self.piece_at(mv.from()) else {
+            return mv.to_string();
+        };
+        let capture = self.piece_at(mv.to()).is_some()
+            || (piece.kind() == PieceKind::Pawn && mv.from().file() != mv.to().file());
+
+        let mut san = String::new();
+        if piece.kind() == PieceKind::Pawn {
+            if capture {
+                san.push(char::from(b'a' + mv.from().file()));
+            }
+        } else {
+            san.push(piece.kind().char().to_ascii_uppercase());
+
+            let rivals: Vec<Move> = self.legal_moves().into_iter()
+                .filter(|other| {
+                    other.to() == mv.to()
+                        && other.from() != mv.from()
+                        && self.piece_at(other.from()) == Some(piece)
+                        && self.castling_side(*other).is_none()
+                })
+                .collect();
+            if !rivals.is_empty() {
+                let file_unique = rivals.iter().all(|other| other.from().file() != mv.from().file());
+                let rank_unique = rivals.iter().all(|other| other.from().rank() != mv.from().rank());
+                let square = mv.from().to_string();
+                if file_unique {
+                    san.push_str(&square[..1]);
+                } else if rank_unique {
+                    san.push_str(&square[1..]);
+                } else {
+                    san.push_str(&square);
+                }
+            }
+        }
+
+        if capture {
+            san.push('x');
+        }
+        san.push_str(&mv.to().to_string());
+        if let Some(promotion) = mv.promotion() {
+            san.push('=');
+            san.push(promotion.char().to_ascii_uppercase());
+        }
+        san
+    }
+
+    /// Returns whether the given move castles king-side (`Some(true)`), queen-side
+    /// (`Some(false)`), or isn't a castling move at all.
+    fn castling_side(&self, mv: Move) -> Option<bool> {
+        let is_king = self.piece_at(mv.from()).is_some_and(|piece| piece.kind() == PieceKind::King);
+        if !is_king || mv.from().rank() != mv.to().rank() || mv.from().file() != 4 {
+            return None;
+        }
+        match mv.to().file() {
+            6 => Some(true),
+            2 => Some(false),
+            _ => None,
+        }
+    }
+
+    /// Rejects a move that could denote any of the given legal moves.
+    pub(crate) fn ambiguous_move(&self, move_str: &str, candidates: &[Move]) -> StockfishError {
+        StockfishError::AmbiguousMove {
+            move_str: move_str.to_owned(),
+            ply: Some(self.ply()),
+            candidates: candidates.iter()
+                .map(|&mv| self.to_san(mv).expect("candidates should be legal"))
+                .collect(),
+        }
+    }
+
+    fn illegal_move(&self, move_str: &str, reason: &str) -> StockfishError {
+        StockfishError::IllegalMove {
+            move_str: move_str.to_owned(),
+            ply: Some(self.ply()),
+            reason: reason.to_owned(),
+        }
+    }
+
+    /// Attributes a move parsing error to the ply that is to be played next.
+    fn at_current_ply(&self, err: StockfishError) -> StockfishError {
+        match err {
+            StockfishError::IllegalMove { move_str, reason, .. } => self.illegal_move(&move_str, &reason),
+            err => err,
+        }
+    }
+}
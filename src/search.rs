@@ -0,0 +1,407 @@
+use std::time::{Duration, Instant};
+
+use crate::engine_eval::EngineEval;
+use crate::engine_output::EngineOutput;
+use crate::error::{Result, StockfishError};
+use crate::pv_line::PvLine;
+use crate::search_info::SearchInfo;
+use crate::stockfish::{self, SharedProcess, Stockfish};
+
+/// An update produced while the engine is searching.
+#[derive(Debug, Clone)]
+pub enum SearchEvent {
+    /// The engine reported progress through an `info` line.
+    Info(SearchInfo),
+    /// The engine finished searching and chose its best move. This is always
+    /// the last event of a search.
+    BestMove(EngineOutput),
+}
+
+/// An iterator over the updates of a running search, obtained through
+/// [`Stockfish::go_streaming`]. Yields a [`SearchEvent::Info`] for every `info`
+/// line that the engine outputs, and ends with a [`SearchEvent::BestMove`].
+///
+/// Dropping the stream before the search has finished sends `"stop"` to the engine,
+/// and the rest of the search's output is discarded before the next command that
+/// awaits a response. (The stream also ends this way after failing to read a line.)
+pub struct SearchStream<'a> {
+    stockfish: &'a mut Stockfish,
+    collector: SearchCollector,
+    finished: bool,
+}
+
+impl<'a> SearchStream<'a> {
+    pub(crate) fn new(stockfish: &'a mut Stockfish, collector: SearchCollector) -> Self {
+        Self { stockfish, collector, finished: false }
+    }
+
+    /// Returns the ranked lines of the search as of the latest `info` lines that
+    /// have been yielded, best line first.
+    #[must_use]
+    pub fn pv_lines(&self) -> Vec<PvLine> {
+        self.collector.pv_lines()
+    }
+}
+
+impl Iterator for SearchStream<'_> {
+    type Item = Result<SearchEvent>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        loop {
+            let line = match self.stockfish.read_line() {
+                Ok(line) => line,
+                Err(err) => {
+                    // The search may still be running
+                    self.stockfish.abandon_search();
+                    self.finished = true;
+                    return Some(Err(err));
+                }
+            };
+
+            // The search is over once "bestmove" is read, even if it can't be parsed
+            self.finished = line.starts_with("bestmove");
+            match self.collector.handle_line(&line) {
+                Ok(None) => {}
+                Ok(Some(SearchUpdate::Info(info))) => return Some(Ok(SearchEvent::Info(info))),
+                Ok(Some(SearchUpdate::BestMove(outcome))) => {
+                    return Some(outcome.engine_output().map(SearchEvent::BestMove));
+                }
+                Err(err) => return Some(Err(err)),
+            }
+        }
+    }
+}
+
+impl Drop for SearchStream<'_> {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.stockfish.abandon_search();
+        }
+    }
+}
+
+/// A handle to a search that runs in the background, obtained through
+/// [`Stockfish::start_search`] or [`Stockfish::start_infinite_search`].
+///
+/// The engine's output is only read when the handle is polled or waited upon, so
+/// none of its methods block for longer than asked to. If the handle is dropped
+/// before the search has finished, `"stop"` is sent to the engine, and the rest of
+/// the search's output is discarded before the next command that awaits a response.
+pub struct SearchHandle<'a> {
+    stockfish: &'a mut Stockfish,
+    collector: SearchCollector,
+    latest_info: Option<SearchInfo>,
+    result: Option<EngineOutput>,
+    finished: bool,
+}
+
+impl<'a> SearchHandle<'a> {
+    pub(crate) fn new(stockfish: &'a mut Stockfish, collector: SearchCollector) -> Self {
+        Self { stockfish, collector, latest_info: None, result: None, finished: false }
+    }
+
+    /// Reads all of the output that the engine has produced so far, without blocking.
+    /// Returns `true` once the search has finished.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`StockfishError`] if an error occurred while trying to
+    /// communicate with the engine.
+    pub fn poll(&mut self) -> Result<bool> {
+        while self.result.is_none() {
+            match self.stockfish.try_read_line(Duration::ZERO)? {
+                Some(line) => self.handle_line(&line)?,
+                None => break,
+            }
+        }
+        Ok(self.result.is_some())
+    }
+
+    /// Returns `true` if the search has finished, as of the last time the handle was polled.
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.result.is_some()
+    }
+
+    /// Returns the most recent scored `info` line that has been read, which describes
+    /// the search's current best line.
+    #[must_use]
+    pub fn latest_info(&self) -> Option<&SearchInfo> {
+        self.latest_info.as_ref()
+    }
+
+    /// Returns the ranked lines of the search as of the output read so far, best line first.
+    #[must_use]
+    pub fn pv_lines(&self) -> Vec<PvLine> {
+        self.collector.pv_lines()
+    }
+
+    /// Returns the result of the search, if it has finished.
+    #[must_use]
+    pub fn result(&self) -> Option<&EngineOutput> {
+        self.result.as_ref()
+    }
+
+    /// Tells the engine to stop searching as soon as possible. The best move found so far
+    /// may then be obtained through [`SearchHandle::wait`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`StockfishError`] if an error occurred while trying to
+    /// communicate with the engine.
+    pub fn stop(&mut self) -> Result<()> {
+        if self.finished {
+            return Ok(());
+        }
+        self.stockfish.uci_send("stop")
+    }
+
+    /// Tells the engine that the opponent has played the move that it was pondering on,
+    /// which turns a search started with [`GoParams::ponder`](crate::GoParams::ponder) into
+    /// a regular one.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use stockfish::{GoParams, Stockfish};
+    /// # fn main() -> Result<(), stockfish::StockfishError> {
+    /// let mut stockfish = Stockfish::new("stockfish.exe")?;
+    /// stockfish.set_option("Ponder", "true")?;
+    ///
+    /// let engine_output = stockfish.go()?;
+    /// stockfish.play_move(engine_output.best_move())?;
+    /// if let Some(expected_move) = engine_output.pondered_move() {
+    ///     // The engine ponders on the position after the expected reply
+    ///     stockfish.play_move(expected_move)?;
+    ///     let mut handle = stockfish.start_search_with(&GoParams::new().ponder().depth(20))?;
+    ///
+    ///     // ...until the opponent actually plays it
+    ///     handle.ponderhit()?;
+    ///     println!("best move: {}", handle.wait()?.best_move());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`StockfishError`] if an error occurred while trying to
+    /// communicate with the engine.
+    pub fn ponderhit(&mut self) -> Result<()> {
+        if self.finished {
+            return Ok(());
+        }
+        self.stockfish.uci_send("ponderhit")
+    }
+
+    /// Returns a [`SearchStopper`], which can stop the search from another thread.
+    #[must_use]
+    pub fn stopper(&self) -> SearchStopper {
+        SearchStopper { process: self.stockfish.shared_process() }
+    }
+
+    /// Blocks until the search has finished, then returns its result.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`StockfishError`] if an error occurred while trying to
+    /// communicate with the engine.
+    pub fn wait(mut self) -> Result<EngineOutput> {
+        while self.result.is_none() {
+            let line = self.stockfish.read_line()?;
+            self.handle_line(&line)?;
+        }
+        self.result.take().ok_or(StockfishError::EngineExited)
+    }
+
+    /// Blocks until the search has finished or the timeout has elapsed, whichever
+    /// comes first. Returns the result of the search if it has finished.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`StockfishError`] if an error occurred while trying to
+    /// communicate with the engine.
+    pub fn wait_timeout(&mut self, timeout: Duration) -> Result<Option<&EngineOutput>> {
+        let deadline = Instant::now() + timeout;
+        while self.result.is_none() {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            match self.stockfish.try_read_line(remaining)? {
+                Some(line) => self.handle_line(&line)?,
+                None => break,
+            }
+        }
+        Ok(self.result.as_ref())
+    }
+
+    fn handle_line(&mut self, line: &str) -> Result<()> {
+        // The search is over once "bestmove" is read, even if it can't be parsed
+        self.finished = line.starts_with("bestmove");
+        match self.collector.handle_line(line)? {
+            Some(SearchUpdate::Info(info)) if info.score().is_some() && info.multipv().unwrap_or(1) == 1 => {
+                self.latest_info = Some(info);
+            }
+            Some(SearchUpdate::BestMove(outcome)) => {
+                self.result = Some(outcome.engine_output()?);
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+impl Drop for SearchHandle<'_> {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.stockfish.abandon_search();
+        }
+    }
+}
+
+/// Stops a search from any thread. Obtained through [`SearchHandle::stopper`].
+#[derive(Clone)]
+pub struct SearchStopper {
+    process: SharedProcess,
+}
+
+impl SearchStopper {
+    /// Tells the engine to stop searching as soon as possible. The search's result
+    /// is then delivered to the [`SearchHandle`] as usual.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`StockfishError`] if an error occurred while trying to
+    /// communicate with the engine.
+    pub fn stop(&self) -> Result<()> {
+        stockfish::send_to_process(&self.process, "stop")
+    }
+}
+
+/// Tracks a search that continues in the background between calls to
+/// [`Stockfish::go_and_ponder`] and [`Stockfish::play_opponent_move`].
+pub(crate) enum PonderState {
+    /// The engine is pondering on `expected_move`, which it expects to be played in reply
+    /// to its own move (the last move of the game's history.)
+    Pondering {
+        expected_move: String,
+        collector: SearchCollector,
+    },
+    /// The engine received a `"ponderhit"`, and is now searching regularly.
+    Searching {
+        collector: SearchCollector,
+    },
+}
+
+/// A line of engine output that is of interest to a search.
+pub(crate) enum SearchUpdate {
+    Info(SearchInfo),
+    BestMove(SearchOutcome),
+}
+
+/// Accumulates the engine's output over the course of one search.
+pub(crate) struct SearchCollector {
+    /// Indexed by MultiPV rank; later iterations overwrite the lines of earlier ones
+    lines: Vec<Option<SearchInfo>>,
+    color_multiplier: i32,
+}
+
+impl SearchCollector {
+    /// The output from stockfish normally displays the value of the evaluation score
+    /// relative to the player with the current move. The multiplier is used to flip it
+    /// such that the score is not relative to the player with the current move.
+    pub(crate) fn new(color_multiplier: i32) -> Self {
+        Self { lines: Vec::new(), color_multiplier }
+    }
+
+    /// Handles one line outputted by the engine during the search, returning the
+    /// update that it represents (if any.)
+    pub(crate) fn handle_line(&mut self, line: &str) -> Result<Option<SearchUpdate>> {
+        let mut segments = line.split(' ');
+        match segments.next() {
+            Some("info") => {
+                let info = SearchInfo::parse(line)?;
+                if info.depth().is_some() && info.score().is_some() {
+                    let index = info.multipv().unwrap_or(1).saturating_sub(1) as usize;
+                    if self.lines.len() <= index {
+                        self.lines.resize(index + 1, None);
+                    }
+                    self.lines[index] = Some(info.clone());
+                }
+                Ok(Some(SearchUpdate::Info(info)))
+            }
+            Some("bestmove") => {
+                let best_move = segments.next()
+                    .ok_or_else(|| StockfishError::malformed(line, "bestmove line should contain a move"))?
+                    .to_owned();
+
+                let pondered_move = if segments.next().is_some_and(|str| str == "ponder") {
+                    Some(segments.next()
+                        .ok_or_else(|| StockfishError::malformed(line, "ponder entry should contain a move"))?
+                        .to_owned())
+                } else {
+                    None
+                };
+
+                Ok(Some(SearchUpdate::BestMove(SearchOutcome {
+                    lines: self.lines.iter().flatten().cloned().collect(),
+                    best_move,
+                    pondered_move,
+                    bestmove_line: line.to_owned(),
+                    color_multiplier: self.color_multiplier,
+                })))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn pv_lines(&self) -> Vec<PvLine> {
+        pv_lines(self.lines.iter().flatten(), self.color_multiplier)
+    }
+}
+
+/// The information gathered from the engine's output over the course of one search.
+pub(crate) struct SearchOutcome {
+    /// The latest scored info line for each MultiPV rank, best line first
+    lines: Vec<SearchInfo>,
+    best_move: String,
+    pondered_move: Option<String>,
+    bestmove_line: String,
+    color_multiplier: i32,
+}
+
+impl SearchOutcome {
+    pub(crate) fn engine_output(self) -> Result<EngineOutput> {
+        let principal = self.lines.first()
+            .and_then(|info| Some((info.depth()?, white_relative(info, self.color_multiplier)?)));
+        let Some((depth, eval)) = principal else {
+            return Err(StockfishError::malformed(&self.bestmove_line, "expected an info line with depth and score before bestmove"));
+        };
+        Ok(EngineOutput::new(eval, self.best_move, self.pondered_move, depth))
+    }
+
+    pub(crate) fn pv_lines(&self) -> Vec<PvLine> {
+        pv_lines(self.lines.iter(), self.color_multiplier)
+    }
+
+    pub(crate) fn color_multiplier(&self) -> i32 {
+        self.color_multiplier
+    }
+}
+
+fn white_relative(info: &SearchInfo, color_multiplier: i32) -> Option<EngineEval> {
+    let eval = info.score()?.eval();
+    Some(EngineEval::new(eval.eval_type(), eval.value() * color_multiplier))
+}
+
+fn pv_lines<'a>(lines: impl Iterator<Item = &'a SearchInfo>, color_multiplier: i32) -> Vec<PvLine> {
+    lines
+        .zip(1..)
+        .filter_map(|(info, rank)| {
+            let eval = white_relative(info, color_multiplier)?;
+            Some(PvLine::new(rank, eval, info.depth()?, info.pv().to_vec()))
+        })
+        .collect()
+}
@@ -0,0 +1,311 @@
+use std::{fmt, str::FromStr, time::Duration};
+
+use crate::engine_eval::{EngineEval, EvalType};
+use crate::error::{Result, StockfishError};
+
+/// Describes whether a score reported by the engine is exact, or only a bound
+/// on the true score (which happens when a search fails high or low.)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ScoreBound {
+    #[default]
+    Exact,
+    /// The true score is at least the reported score (`lowerbound`).
+    Lower,
+    /// The true score is at most the reported score (`upperbound`).
+    Upper,
+}
+
+/// Represents a score reported within an `info` line.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Score {
+    eval: EngineEval,
+    bound: ScoreBound,
+}
+
+impl Score {
+
+    #[must_use]
+    pub fn new(eval: EngineEval, bound: ScoreBound) -> Self {
+        Self { eval, bound }
+    }
+
+    /// Returns the evaluation itself. As reported by the engine, this is
+    /// relative to the side to move.
+    #[must_use]
+    pub fn eval(&self) -> EngineEval {
+        self.eval
+    }
+
+    /// Returns a [`ScoreBound`] describing whether the score is exact.
+    #[must_use]
+    pub fn bound(&self) -> ScoreBound {
+        self.bound
+    }
+}
+impl fmt::Display for Score {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.eval)?;
+        match self.bound {
+            ScoreBound::Exact => Ok(()),
+            ScoreBound::Lower => write!(f, " lowerbound"),
+            ScoreBound::Upper => write!(f, " upperbound"),
+        }
+    }
+}
+
+/// The win/draw/loss statistics reported by the engine (in permille), from the
+/// perspective of the side to move.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Wdl {
+    win: u32,
+    draw: u32,
+    loss: u32,
+}
+
+impl Wdl {
+
+    #[must_use]
+    pub fn new(win: u32, draw: u32, loss: u32) -> Self {
+        Self { win, draw, loss }
+    }
+
+    #[must_use]
+    pub fn win(&self) -> u32 {
+        self.win
+    }
+
+    #[must_use]
+    pub fn draw(&self) -> u32 {
+        self.draw
+    }
+
+    #[must_use]
+    pub fn loss(&self) -> u32 {
+        self.loss
+    }
+}
+
+/// Represents one `info` line outputted by the engine while it is searching.
+/// Every field is optional, as the engine only reports some of them in each line.
+///
+/// # Example
+///
+/// ```rust
+/// use stockfish::SearchInfo;
+/// # fn main() -> Result<(), stockfish::StockfishError> {
+/// let info = SearchInfo::parse(
+///     "info depth 12 seldepth 16 multipv 1 score cp 31 nodes 41231 nps 824620 \
+///     hashfull 12 tbhits 0 time 50 pv e2e4 e7e5 g1f3"
+/// )?;
+/// assert_eq!(info.depth(), Some(12));
+/// assert_eq!(info.score().map(|score| score.eval().value()), Some(31));
+/// assert_eq!(info.pv(), ["e2e4", "e7e5", "g1f3"]);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SearchInfo {
+    depth: Option<u32>,
+    seldepth: Option<u32>,
+    multipv: Option<u32>,
+    score: Option<Score>,
+    wdl: Option<Wdl>,
+    nodes: Option<u64>,
+    nps: Option<u64>,
+    hashfull: Option<u32>,
+    tbhits: Option<u64>,
+    time: Option<Duration>,
+    pv: Vec<String>,
+    currmove: Option<String>,
+    currmovenumber: Option<u32>,
+    string: Option<String>,
+}
+
+/// Keywords which may follow one another within an `info` line.
+const KEYWORDS: [&str; 18] = [
+    "depth", "seldepth", "multipv", "score", "wdl", "nodes", "nps", "hashfull", "tbhits", "sbhits",
+    "time", "pv", "currmove", "currmovenumber", "cpuload", "string", "refutation", "currline",
+];
+
+impl SearchInfo {
+
+    /// Parses an `info` line outputted by the engine. Unrecognized fields are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StockfishError::MalformedOutput`] if the line isn't an `info` line or if
+    /// one of its fields has an invalid value, or [`StockfishError::UnknownEvalDescriptor`]
+    /// if a score is neither `cp` nor `mate`.
+    pub fn parse(line: &str) -> Result<SearchInfo> {
+        let segments: Vec<&str> = line.split_whitespace().collect();
+        if segments.first() != Some(&"info") {
+            return Err(StockfishError::malformed(line, "info line should begin with \"info\""));
+        }
+
+        let mut info = SearchInfo::default();
+        let mut i = 1;
+
+        while let Some(keyword) = segments.get(i) {
+            i += 1;
+
+            // Takes the segment following the keyword and parses it into the required type
+            macro_rules! next_value {
+                () => {{
+                    let value = segments.get(i)
+                        .and_then(|segment| segment.parse().ok())
+                        .ok_or_else(|| StockfishError::malformed(line, &format!("invalid value for {keyword}")))?;
+                    i += 1;
+                    value
+                }};
+            }
+
+            match *keyword {
+                "depth" => info.depth = Some(next_value!()),
+                "seldepth" => info.seldepth = Some(next_value!()),
+                "multipv" => info.multipv = Some(next_value!()),
+                "nodes" => info.nodes = Some(next_value!()),
+                "nps" => info.nps = Some(next_value!()),
+                "hashfull" => info.hashfull = Some(next_value!()),
+                "tbhits" => info.tbhits = Some(next_value!()),
+                "time" => info.time = Some(Duration::from_millis(next_value!())),
+                "currmove" => info.currmove = Some(next_value!()),
+                "currmovenumber" => info.currmovenumber = Some(next_value!()),
+                "score" => {
+                    let eval_type = segments.get(i)
+                        .ok_or_else(|| StockfishError::malformed(line, "score should have a type"))?;
+                    let eval_type = EvalType::from_descriptor(eval_type)?;
+                    i += 1;
+                    let eval = EngineEval::new(eval_type, next_value!());
+
+                    let bound = match segments.get(i) {
+                        Some(&"lowerbound") => ScoreBound::Lower,
+                        Some(&"upperbound") => ScoreBound::Upper,
+                        _ => ScoreBound::Exact,
+                    };
+                    if bound != ScoreBound::Exact {
+                        i += 1;
+                    }
+                    info.score = Some(Score::new(eval, bound));
+                }
+                "wdl" => {
+                    let (win, draw, loss) = (next_value!(), next_value!(), next_value!());
+                    info.wdl = Some(Wdl::new(win, draw, loss));
+                }
+                "pv" => {
+                    while let Some(segment) = segments.get(i).filter(|segment| !KEYWORDS.contains(segment)) {
+                        info.pv.push((*segment).to_owned());
+                        i += 1;
+                    }
+                }
+                "string" => {
+                    // The string spans the remainder of the line, including its spacing
+                    let string = line.split_once(" string").map_or("", |(_, string)| string);
+                    info.string = Some(string.trim_start().to_owned());
+                    break;
+                }
+                _ => {
+                    // Skip the values of fields that aren't recorded
+                    while segments.get(i).is_some_and(|segment| !KEYWORDS.contains(segment)) {
+                        i += 1;
+                    }
+                }
+            }
+        }
+
+        Ok(info)
+    }
+
+    /// Returns the search depth, in plies.
+    #[must_use]
+    pub fn depth(&self) -> Option<u32> {
+        self.depth
+    }
+
+    /// Returns the selective search depth, in plies.
+    #[must_use]
+    pub fn seldepth(&self) -> Option<u32> {
+        self.seldepth
+    }
+
+    /// Returns the rank of the line that this info describes, when searching
+    /// in MultiPV mode. (Ranks start from 1.)
+    #[must_use]
+    pub fn multipv(&self) -> Option<u32> {
+        self.multipv
+    }
+
+    /// Returns the [`Score`] of the line, relative to the side to move.
+    #[must_use]
+    pub fn score(&self) -> Option<Score> {
+        self.score
+    }
+
+    /// Returns the win/draw/loss statistics of the line, if the engine reports them.
+    /// (For Stockfish, this requires the `"UCI_ShowWDL"` option.)
+    #[must_use]
+    pub fn wdl(&self) -> Option<Wdl> {
+        self.wdl
+    }
+
+    /// Returns the number of nodes searched.
+    #[must_use]
+    pub fn nodes(&self) -> Option<u64> {
+        self.nodes
+    }
+
+    /// Returns the number of nodes searched per second.
+    #[must_use]
+    pub fn nps(&self) -> Option<u64> {
+        self.nps
+    }
+
+    /// Returns how full the hashtable is, in permille.
+    #[must_use]
+    pub fn hashfull(&self) -> Option<u32> {
+        self.hashfull
+    }
+
+    /// Returns the number of positions found in the endgame tablebases.
+    #[must_use]
+    pub fn tbhits(&self) -> Option<u64> {
+        self.tbhits
+    }
+
+    /// Returns the time that has been spent searching.
+    #[must_use]
+    pub fn time(&self) -> Option<Duration> {
+        self.time
+    }
+
+    /// Returns the principal variation, in long UCI algebraic notation.
+    /// Empty if the line didn't include a principal variation.
+    #[must_use]
+    pub fn pv(&self) -> &[String] {
+        &self.pv
+    }
+
+    /// Returns the move currently being searched.
+    #[must_use]
+    pub fn currmove(&self) -> Option<&str> {
+        self.currmove.as_deref()
+    }
+
+    /// Returns the number of the move currently being searched. (Numbers start from 1.)
+    #[must_use]
+    pub fn currmovenumber(&self) -> Option<u32> {
+        self.currmovenumber
+    }
+
+    /// Returns the free-form text of an `info string` line.
+    #[must_use]
+    pub fn string(&self) -> Option<&str> {
+        self.string.as_deref()
+    }
+}
+impl FromStr for SearchInfo {
+    type Err = StockfishError;
+
+    fn from_str(str: &str) -> Result<Self> {
+        SearchInfo::parse(str)
+    }
+}
@@ -0,0 +1,182 @@
+use std::fmt;
+
+/// A sequential probability ratio test, which decides between two hypotheses about the
+/// (logistic) Elo difference between two engines as the results of game pairs come in:
+/// H0, that the difference is `elo0`, and H1, that it is `elo1`. `alpha` and `beta` are the
+/// probabilities of accepting H1 when H0 holds, and of accepting H0 when H1 holds.
+///
+/// The test works on the pentanomial distribution of the scores of game pairs (two games
+/// from the same opening, with colors reversed), which accounts for the correlation
+/// between the two games of a pair. See [`EngineMatch::sprt`](crate::EngineMatch::sprt) to
+/// have a match stop once the test concludes.
+///
+/// # Example
+///
+/// ```rust
+/// use stockfish::{Sprt, SprtStatus};
+///
+/// let sprt = Sprt::new(0.0, 5.0, 0.05, 0.05);
+/// // The numbers of game pairs in which the first engine scored 0, 0.5, 1, 1.5 and 2 points
+/// let pentanomial = [400, 2000, 5000, 2100, 500];
+/// assert!(sprt.llr(&pentanomial) > sprt.upper_bound());
+/// assert_eq!(sprt.status(&pentanomial), SprtStatus::AcceptH1);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sprt {
+    elo0: f64,
+    elo1: f64,
+    alpha: f64,
+    beta: f64,
+}
+
+impl Sprt {
+
+    /// Creates a test of H0 (an Elo difference of `elo0`) against H1 (an Elo difference of
+    /// `elo1`), with the given error probabilities.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `elo0` isn't less than `elo1`, or if `alpha` or `beta` isn't strictly
+    /// between 0 and 1.
+    #[must_use]
+    pub fn new(elo0: f64, elo1: f64, alpha: f64, beta: f64) -> Self {
+        assert!(elo0 < elo1, "elo0 should be less than elo1");
+        assert!(alpha > 0.0 && alpha < 1.0, "alpha should be between 0 and 1");
+        assert!(beta > 0.0 && beta < 1.0, "beta should be between 0 and 1");
+        Self { elo0, elo1, alpha, beta }
+    }
+
+    #[must_use]
+    pub fn elo0(&self) -> f64 {
+        self.elo0
+    }
+
+    #[must_use]
+    pub fn elo1(&self) -> f64 {
+        self.elo1
+    }
+
+    #[must_use]
+    pub fn alpha(&self) -> f64 {
+        self.alpha
+    }
+
+    #[must_use]
+    pub fn beta(&self) -> f64 {
+        self.beta
+    }
+
+    /// Returns the log-likelihood ratio at or below which H0 is accepted.
+    #[must_use]
+    pub fn lower_bound(&self) -> f64 {
+        (self.beta / (1.0 - self.alpha)).ln()
+    }
+
+    /// Returns the log-likelihood ratio at or above which H1 is accepted.
+    #[must_use]
+    pub fn upper_bound(&self) -> f64 {
+        ((1.0 - self.beta) / self.alpha).ln()
+    }
+
+    /// Returns the log-likelihood ratio of H1 over H0, given the numbers of game pairs in
+    /// which the first engine scored 0, 0.5, 1, 1.5 and 2 points.
+    #[must_use]
+    pub fn llr(&self, pentanomial: &[u32; 5]) -> f64 {
+        let pairs: u32 = pentanomial.iter().sum();
+        if pairs == 0 {
+            return 0.0;
+        }
+
+        // Empty outcomes are given a tiny count, so that every outcome stays possible
+        let counts = pentanomial.map(|count| if count == 0 {1e-3} else {f64::from(count)});
+        let total: f64 = counts.iter().sum();
+        let probabilities = counts.map(|count| count / total);
+
+        let expected0 = expected_distribution(&probabilities, score_from_elo(self.elo0));
+        let expected1 = expected_distribution(&probabilities, score_from_elo(self.elo1));
+        let llr_per_pair: f64 = (0..5)
+            .map(|index| probabilities[index] * (expected1[index].ln() - expected0[index].ln()))
+            .sum();
+        total * llr_per_pair
+    }
+
+    /// Returns the outcome of the test, given the numbers of game pairs in which the first
+    /// engine scored 0, 0.5, 1, 1.5 and 2 points.
+    #[must_use]
+    pub fn status(&self, pentanomial: &[u32; 5]) -> SprtStatus {
+        let llr = self.llr(pentanomial);
+        if llr >= self.upper_bound() {
+            SprtStatus::AcceptH1
+        } else if llr <= self.lower_bound() {
+            SprtStatus::AcceptH0
+        } else {
+            SprtStatus::Continue
+        }
+    }
+}
+
+impl fmt::Display for Sprt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "SPRT elo0={} elo1={} alpha={} beta={} (bounds {:.2}, {:.2})",
+            self.elo0, self.elo1, self.alpha, self.beta, self.lower_bound(), self.upper_bound(),
+        )
+    }
+}
+
+/// The outcome of a [`Sprt`] so far.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SprtStatus {
+    /// Neither hypothesis can be accepted yet; more games are needed.
+    Continue,
+    /// H0 was accepted: the Elo difference is likely `elo0` or less.
+    AcceptH0,
+    /// H1 was accepted: the Elo difference is likely `elo1` or more.
+    AcceptH1,
+}
+
+impl fmt::Display for SprtStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", match self {
+            SprtStatus::Continue => "continue",
+            SprtStatus::AcceptH0 => "H0 accepted",
+            SprtStatus::AcceptH1 => "H1 accepted",
+        })
+    }
+}
+
+/// The score per game of each pentanomial outcome (0, 0.5, 1, 1.5 or 2 points in two games.)
+const PAIR_SCORES: [f64; 5] = [0.0, 0.25, 0.5, 0.75, 1.0];
+
+/// Converts a logistic Elo difference into an expected score.
+fn score_from_elo(elo: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf(-elo / 400.0))
+}
+
+/// Returns the distribution closest to the given one (the maximum likelihood estimate)
+/// whose expected score per game is `score`.
+///
+/// The solution has the form `p_i / (1 + x (a_i - score))`, where `x` is the root of
+/// `sum p_i (a_i - score) / (1 + x (a_i - score)) = 0`, which is found by bisection.
+fn expected_distribution(probabilities: &[f64; 5], score: f64) -> [f64; 5] {
+    let deviations = PAIR_SCORES.map(|pair_score| pair_score - score);
+    let equation = |x: f64| -> f64 {
+        (0..5).map(|index| probabilities[index] * deviations[index] / (1.0 + x * deviations[index])).sum()
+    };
+
+    // Every denominator stays positive strictly between these bounds
+    let mut low = -1.0 / (1.0 - score);
+    let mut high = 1.0 / score;
+    // The equation decreases in x
+    for _ in 0..100 {
+        let middle = (low + high) / 2.0;
+        if equation(middle) > 0.0 {
+            low = middle;
+        } else {
+            high = middle;
+        }
+    }
+    let x = (low + high) / 2.0;
+    std::array::from_fn(|index| probabilities[index] / (1.0 + x * deviations[index]))
+}
@@ -1,63 +1,134 @@
 use std::{
     io,
     process::Command,
-    sync::{mpsc, mpsc::Receiver},
-    time::Duration,
+    sync::{mpsc, mpsc::{Receiver, RecvTimeoutError}, Arc, Mutex, PoisonError},
+    time::{Duration, Instant},
     string::ToString,
 };
 
 use interactive_process::InteractiveProcess;
 
-use crate::engine_eval::{EngineEval, EvalType};
+use crate::engine_options::{EngineOption, EngineOptions, OptionType, OptionValue};
 use crate::engine_output::EngineOutput;
+use crate::go_params::GoParams;
+use crate::pgn::PgnGame;
+use crate::polyglot::{BookSelection, PolyglotBook};
+use crate::pv_line::PvLine;
+use crate::error::{Result, StockfishError};
+use crate::position::{Color, Position, STARTING_FEN};
+use crate::search::{PonderState, SearchCollector, SearchHandle, SearchOutcome, SearchStream, SearchUpdate};
+
+/// The engine process, which may be shared with a [`SearchStopper`](crate::SearchStopper)
+/// so that commands can be sent from other threads. Set to [`None`] once the watchdog
+/// kills the process.
+pub(crate) type SharedProcess = Arc<Mutex<Option<InteractiveProcess>>>;
+
+/// How long the engine is given to complete the UCI handshake, since no response
+/// timeout can be set before then.
+pub(crate) const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The settings of a [`Stockfish`] instance that outlast a game, as recorded by
+/// [`Stockfish::settings`]. (Used by [`EnginePool`](crate::EnginePool) to restore
+/// each engine before a job.)
+pub(crate) struct Settings {
+    depth: u32,
+    option_values: Vec<(String, OptionValue)>,
+    response_timeout: Option<Duration>,
+    ensure_ready_timeout: Option<Duration>,
+    watchdog: bool,
+    book: Option<(PolyglotBook, BookSelection)>,
+}
 
 /// The interface for interacting with a Stockfish process.
 pub struct Stockfish {
-    interactive_process: InteractiveProcess,
-    receiver: Receiver<String>,
+    interactive_process: SharedProcess,
+    receiver: Receiver<io::Result<String>>,
     depth: u32,
     version: Option<String>,
+    engine_name: Option<String>,
+    engine_author: Option<String>,
+    options: EngineOptions,
+    root_fen: Option<String>,
+    moves: Vec<String>,
+    position: Position,
+    last_command: String,
+    response_timeout: Option<Duration>,
+    ensure_ready_timeout: Option<Duration>,
+    watchdog: bool,
+    ponder_state: Option<PonderState>,
+    book: Option<(PolyglotBook, BookSelection)>,
+    /// The beginnings of lines still owed by the engine to commands that failed before
+    /// reading them. These lines are discarded before the next command awaiting a response.
+    stale_responses: Vec<&'static str>,
 }
 
 impl Stockfish {
 
     /// Given the path to the Stockfish binary executable, this function
-    /// initiates an [`InteractiveProcess`] for the executable, and returns an instance
-    /// of the [Stockfish] wrapper class.
+    /// initiates an [`InteractiveProcess`] for the executable, performs the UCI
+    /// handshake, and returns an instance of the [Stockfish] wrapper class.
+    /// 
+    /// During the handshake, the engine's `id` and `option` declarations are recorded
+    /// (skipping any malformed `option` line); they may be inspected through [`Stockfish::get_engine_name`],
+    /// [`Stockfish::get_engine_author`] and [`Stockfish::get_options`].
+    /// 
+    /// An engine that doesn't complete the handshake within 30 seconds is killed.
     /// 
     /// # Example
     /// 
-    /// ```rust
+    /// ```no_run
     /// use stockfish::Stockfish;
-    /// let stockfish = Stockfish::new("stockfish.exe").unwrap();
+    /// # fn main() -> Result<(), stockfish::StockfishError> {
+    /// let stockfish = Stockfish::new("stockfish.exe")?;
+    /// println!("{:?} by {:?}", stockfish.get_engine_name(), stockfish.get_engine_author());
+    /// # Ok(())
+    /// # }
     /// ```
     /// 
     /// # Errors
     /// 
-    /// Returns an [`io::Error`] if an error occurred while trying to
-    /// create/communicate with the engine.
-    pub fn new(path: &str) -> io::Result<Stockfish> {
+    /// Returns a [`StockfishError`] if an error occurred while trying to
+    /// create/communicate with the engine, or [`StockfishError::Timeout`] if the
+    /// handshake didn't complete in time.
+    pub fn new(path: &str) -> Result<Stockfish> {
         let mut command = Command::new(path);
 
         let (tx, rx) = mpsc::channel();
 
         let proc = InteractiveProcess::new(&mut command, move |line| {
-            let line = line.unwrap();
             let send_result = tx.send(line);
             if send_result.is_err() {
                 println!("receiving end of mpsc channel disconnected");
             }
         })?;
 
-        let first_line = rx.recv().expect("stockfish process should have outputted a first line");
-        let version = first_line.split(' ').nth(1).map(ToString::to_string);
-
-        Ok(Stockfish { 
-            interactive_process: proc,
+        let mut stockfish = Stockfish {
+            interactive_process: Arc::new(Mutex::new(Some(proc))),
             receiver: rx,
             depth: 15,
-            version
-        })
+            version: None,
+            engine_name: None,
+            engine_author: None,
+            options: EngineOptions::default(),
+            root_fen: None,
+            moves: Vec::new(),
+            position: Position::starting(),
+            last_command: String::new(),
+            response_timeout: None,
+            ensure_ready_timeout: None,
+            watchdog: false,
+            ponder_state: None,
+            book: None,
+            stale_responses: Vec::new(),
+        };
+
+        if let Err(error) = stockfish.uci_handshake() {
+            // Otherwise, a stalled engine would outlive the wrapper
+            stockfish.kill();
+            return Err(error);
+        }
+
+        Ok(stockfish)
     }
 
     /// Prepares the Stockfish process for a new game. Should be called
@@ -66,16 +137,21 @@ impl Stockfish {
     /// 
     /// # Example
     /// 
-    /// ```rust
+    /// ```no_run
+    /// # use stockfish::Stockfish;
+    /// # fn main() -> Result<(), stockfish::StockfishError> {
     /// let mut stockfish = Stockfish::new("stockfish.exe")?;
     /// stockfish.setup_for_new_game()?;
+    /// # Ok(())
+    /// # }
     /// ```
     /// 
     /// # Errors
     /// 
-    /// Returns an [`io::Error`] if an error occurred while trying to
+    /// Returns a [`StockfishError`] if an error occurred while trying to
     /// communicate with the engine. 
-    pub fn setup_for_new_game(&mut self) -> io::Result<()> {
+    pub fn setup_for_new_game(&mut self) -> Result<()> {
+        self.stop_pondering()?;
         self.ensure_ready()?;
         self.uci_send("ucinewgame")?;
         Ok(())
@@ -86,20 +162,66 @@ impl Stockfish {
     /// 
     /// # Example
     /// 
-    /// ```rust
+    /// ```no_run
+    /// # use stockfish::Stockfish;
+    /// # fn main() -> Result<(), stockfish::StockfishError> {
     /// let mut stockfish = Stockfish::new("stockfish.exe")?;
     /// stockfish.set_fen_position("r1bqk2r/ppppppbp/2n2np1/8/8/2N2NP1/PPPPPPBP/R1BQK2R w KQkq - 0 1")?;
     /// stockfish.print_board()?;
+    /// # Ok(())
+    /// # }
     /// ```
     /// 
     /// # Errors
     /// 
-    /// Returns an [`io::Error`] if an error occurred while trying to
-    /// communicate with the engine. 
-    pub fn set_fen_position(&mut self, fen: &str) -> io::Result<()> {
-        let msg = String::from("position fen ") + fen;
-        self.uci_send(&msg)?;
-        Ok(())
+    /// Returns a [`StockfishError`] if an error occurred while trying to
+    /// communicate with the engine, or [`StockfishError::IllegalPosition`] if
+    /// the FEN is malformed. (Malformed FENs are rejected before being sent, as
+    /// they may otherwise crash the engine.)
+    pub fn set_fen_position(&mut self, fen: &str) -> Result<()> {
+        let position = Position::from_fen(fen)?;
+        self.stop_pondering()?;
+        self.root_fen = Some(fen.to_owned());
+        self.moves.clear();
+        self.position = position;
+        self.send_position()
+    }
+
+    /// Sets the current chess position to that of the given game after the given number
+    /// of plies of its mainline (the game's starting position for 0.) The game's moves up
+    /// to that ply are sent as the move history, so that Stockfish can take repetitions
+    /// into account.
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// # use stockfish::{PgnGame, Stockfish};
+    /// # fn main() -> Result<(), stockfish::StockfishError> {
+    /// let mut stockfish = Stockfish::new("stockfish.exe")?;
+    /// let game = PgnGame::parse("1. e4 e5 2. Nf3 Nc6 3. Bb5 a6 *")?;
+    /// 
+    /// // Analyze every position of the game
+    /// for ply in 0..=game.moves().len() {
+    ///     stockfish.set_pgn_position(&game, ply)?;
+    ///     println!("{}", stockfish.go()?);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// 
+    /// # Errors
+    /// 
+    /// Returns a [`StockfishError`] if an error occurred while trying to
+    /// communicate with the engine, or [`StockfishError::PlyOutOfRange`] if `ply` exceeds
+    /// the number of moves in the game's mainline.
+    pub fn set_pgn_position(&mut self, game: &PgnGame, ply: usize) -> Result<()> {
+        let position = game.position_at(ply)?;
+        self.stop_pondering()?;
+        self.root_fen = game.root_fen().map(str::to_owned);
+        self.moves = game.uci_moves();
+        self.moves.truncate(ply);
+        self.position = position;
+        self.send_position()
     }
 
     /// Reverts the current chess position to the default starting position.
@@ -108,22 +230,29 @@ impl Stockfish {
     /// 
     /// # Example
     /// 
-    /// ```rust
+    /// ```no_run
+    /// # use stockfish::Stockfish;
+    /// # fn main() -> Result<(), stockfish::StockfishError> {
     /// let mut stockfish = Stockfish::new("stockfish.exe")?;
     /// stockfish.set_fen_position("r1bqk2r/ppppppbp/2n2np1/8/8/2N2NP1/PPPPPPBP/R1BQK2R w KQkq - 0 1")?;
     /// stockfish.reset_position()?;
     /// 
     /// // See that the board has been reverted to the default position
     /// stockfish.print_board()?;
+    /// # Ok(())
+    /// # }
     /// ```
     /// 
     /// # Errors
     /// 
-    /// Returns an [`io::Error`] if an error occurred while trying to
+    /// Returns a [`StockfishError`] if an error occurred while trying to
     /// communicate with the engine. 
-    pub fn reset_position(&mut self) -> io::Result<()> {
-        self.uci_send("position startpos")?;
-        Ok(())
+    pub fn reset_position(&mut self) -> Result<()> {
+        self.stop_pondering()?;
+        self.root_fen = None;
+        self.moves.clear();
+        self.position = Position::starting();
+        self.send_position()
     }
 
     /// This should be called to ensure that the Stockfish process is ready
@@ -132,61 +261,67 @@ impl Stockfish {
     /// 
     /// # Example
     /// 
-    /// ```rust
+    /// ```no_run
+    /// # use stockfish::Stockfish;
+    /// # fn main() -> Result<(), stockfish::StockfishError> {
     /// let mut stockfish = Stockfish::new("stockfish.exe")?;
     /// stockfish.ensure_ready()?;
     /// stockfish.setup_for_new_game()?;
+    /// # Ok(())
+    /// # }
     /// ```
     /// 
     /// # Errors
     /// 
-    /// Returns an [`io::Error`] if an error occurred while trying to
-    /// communicate with the engine. 
-    pub fn ensure_ready(&mut self) -> io::Result<()> {
+    /// Returns a [`StockfishError`] if an error occurred while trying to
+    /// communicate with the engine, or [`StockfishError::Timeout`] if `"readyok"`
+    /// wasn't received within the timeout set by [`Stockfish::set_ensure_ready_timeout`].
+    pub fn ensure_ready(&mut self) -> Result<()> {
         self.uci_send("isready")?;
-        while self.read_line() != "readyok" {}
-        Ok(())
+        // Until it is read, "readyok" is owed like any late output
+        self.stale_responses.push("readyok");
+        let deadline = self.ensure_ready_timeout.map(|timeout| Instant::now() + timeout);
+        self.discard_stale_responses(deadline)
     }
 
     /// Returns a string Forsyth-Edwards notation (FEN) describing the current chess position
     /// in which Stockfish is playing.
     /// 
+    /// The position is maintained by the wrapper itself as moves are played, so the engine
+    /// isn't queried. (While Stockfish is pondering, this is the position before the
+    /// opponent's expected reply.)
+    /// 
     /// # Example
     /// 
-    /// ```rust
+    /// ```no_run
+    /// # use stockfish::Stockfish;
+    /// # fn main() -> Result<(), stockfish::StockfishError> {
     /// let mut stockfish = Stockfish::new("stockfish.exe")?;
     /// stockfish.play_move("e2e4")?;
     /// 
-    /// let fen = stockfish.get_fen()?;
+    /// let fen = stockfish.get_fen();
     /// println!("fen after move was played: {fen}");
+    /// # Ok(())
+    /// # }
     /// ```
-    /// 
-    /// # Errors
-    /// 
-    /// Returns an [`io::Error`] if an error occurred while trying to
-    /// communicate with the engine.
-    pub fn get_fen(&mut self) -> io::Result<String> {
-        self.uci_send("d")?;
-        loop {
-            let line = self.read_line();
-            let mut segments= line.split(' ');
-            if segments.next().unwrap() == "Fen:" {
-                let fen = segments.collect::<Vec<&str>>().join(" ");
-
-                // Keep reading lines until reached "Checkers", which is in the last line
-                while !self.read_line().contains("Checkers") {}
-
-                return Ok(fen);
-            }
-        }
+    #[must_use]
+    pub fn get_fen(&self) -> String {
+        self.position.to_fen()
     }
 
     /// Plays a move on the current chess position in which Stockfish is playing.
     /// This function only updates the board; it does not prompt Stockfish to begin calculating.
     /// 
+    /// Besides long UCI algebraic notation (e.g., `"e2e4"`), the move may be given in
+    /// standard algebraic notation or as a person would type it (e.g., `"e4"`, `"Ng1-f3"`
+    /// or `"0-0"`); see [`Position::parse_move`]. It is recorded in the move history in
+    /// long UCI algebraic notation.
+    /// 
     /// # Example
     /// 
-    /// ```rust
+    /// ```no_run
+    /// # use stockfish::Stockfish;
+    /// # fn main() -> Result<(), stockfish::StockfishError> {
     /// let mut stockfish = Stockfish::new("stockfish.exe")?;
     /// 
     /// stockfish.print_board()?;
@@ -195,25 +330,29 @@ impl Stockfish {
     /// 
     /// // See that the move has been played on the board
     /// stockfish.print_board()?;
+    /// # Ok(())
+    /// # }
     /// ```
     /// 
     /// # Errors
     /// 
-    /// Returns an [`io::Error`] if an error occurred while trying to
-    /// communicate with the engine. 
-    pub fn play_move(&mut self, move_str: &str) -> io::Result<()> {
-        let fen = self.get_fen()?;
-        let data = format!("position fen {fen} moves {move_str}");
-        self.uci_send(&data)?;
-        Ok(())
+    /// Returns a [`StockfishError`] if an error occurred while trying to
+    /// communicate with the engine, [`StockfishError::AmbiguousMove`] if the move could
+    /// denote several legal moves, or [`StockfishError::IllegalMove`] if it is malformed
+    /// or illegal in the current position.
+    pub fn play_move(&mut self, move_str: &str) -> Result<()> {
+        self.play_moves(&[move_str])
     }
 
     /// Plays a sequence of moves on the current chess position in which Stockfish is playing.
     /// This function only updates the board; it does not prompt Stockfish to begin calculating.
+    /// The moves are read as with [`Stockfish::play_move`].
     /// 
     /// # Example
     /// 
-    /// ```rust
+    /// ```no_run
+    /// # use stockfish::Stockfish;
+    /// # fn main() -> Result<(), stockfish::StockfishError> {
     /// let mut stockfish = Stockfish::new("stockfish.exe")?;
     /// 
     /// stockfish.print_board()?;
@@ -223,19 +362,122 @@ impl Stockfish {
     /// 
     /// // See that the moves have been played on the board
     /// stockfish.print_board()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// 
+    /// If Stockfish is pondering, the pondering is stopped first, so that the moves are played
+    /// after the opponent's expected reply (see [`Stockfish::stop_pondering`]):
+    /// 
+    /// ```no_run
+    /// # use stockfish::{Position, Stockfish};
+    /// # fn main() -> Result<(), stockfish::StockfishError> {
+    /// let mut stockfish = Stockfish::new("stockfish.exe")?;
+    /// stockfish.go_and_ponder()?; // e.g., plays e2e4, then ponders on e7e5
+    /// stockfish.play_move("g1f3")?;
+    /// 
+    /// // The board matches the moves sent to the engine
+    /// let mut position = Position::from_fen(stockfish.get_root_fen())?;
+    /// for move_str in stockfish.get_move_history() {
+    ///     position.play_uci(move_str)?;
+    /// }
+    /// assert_eq!(stockfish.get_fen(), position.to_fen());
+    /// # Ok(())
+    /// # }
     /// ```
     /// 
     /// # Errors
     /// 
-    /// Returns an [`io::Error`] if an error occurred while trying to
-    /// communicate with the engine. 
-    pub fn play_moves(&mut self, moves: &[&str]) -> io::Result<()> {
-        let fen = self.get_fen()?;
-        let moves = moves.join(" ");
+    /// Returns a [`StockfishError`] if an error occurred while trying to
+    /// communicate with the engine, or [`StockfishError::AmbiguousMove`] or
+    /// [`StockfishError::IllegalMove`] if a move doesn't denote exactly one legal move, which
+    /// names the move, its ply and the candidates or the reason it was rejected. (In that
+    /// case, none of the moves are played.)
+    pub fn play_moves(&mut self, moves: &[&str]) -> Result<()> {
+        // The pondered reply, if any, becomes part of the position
+        self.stop_pondering()?;
 
-        let data = format!("position fen {fen} moves {moves}");
-        self.uci_send(&data)?;
-        Ok(())
+        // Check every move before any of them are played
+        let mut position = self.position.clone();
+        let mut played = Vec::with_capacity(moves.len());
+        for move_str in moves {
+            let mv = position.parse_move(move_str)?;
+            position.play(mv)?;
+            played.push(mv.to_string());
+        }
+
+        self.moves.extend(played);
+        self.position = position;
+        self.send_position()
+    }
+
+    /// Plays a move, given in standard algebraic notation (e.g., `"Nf3"` or `"O-O"`), on
+    /// the current chess position in which Stockfish is playing. The move is recorded in the
+    /// move history in long UCI algebraic notation.
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// # use stockfish::Stockfish;
+    /// # fn main() -> Result<(), stockfish::StockfishError> {
+    /// let mut stockfish = Stockfish::new("stockfish.exe")?;
+    /// 
+    /// stockfish.play_san("e4")?;
+    /// stockfish.play_san("e5")?;
+    /// stockfish.play_san("Nf3")?;
+    /// assert_eq!(stockfish.get_move_history(), ["e2e4", "e7e5", "g1f3"]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// 
+    /// # Errors
+    /// 
+    /// Returns a [`StockfishError`] if an error occurred while trying to
+    /// communicate with the engine, or [`StockfishError::AmbiguousMove`] or
+    /// [`StockfishError::IllegalMove`] if the move doesn't denote exactly one legal move in
+    /// the current position.
+    pub fn play_san(&mut self, san: &str) -> Result<()> {
+        let mv = self.position.parse_san(san)?;
+        self.play_move(&mv.to_string())
+    }
+
+    /// Returns the moves that have been played since the root position (the position
+    /// last set through [`Stockfish::set_fen_position`] or [`Stockfish::reset_position`]),
+    /// in long UCI algebraic notation.
+    /// 
+    /// The root position and these moves are sent to the engine in full whenever the position
+    /// changes, so that it can take the game's history into account (e.g., to detect
+    /// threefold repetitions.) Positions sent directly through [`Stockfish::uci_send`] aren't tracked.
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// # use stockfish::Stockfish;
+    /// # fn main() -> Result<(), stockfish::StockfishError> {
+    /// let mut stockfish = Stockfish::new("stockfish.exe")?;
+    /// stockfish.play_moves(&["e2e4", "e7e5"])?;
+    /// stockfish.play_move("g1f3")?;
+    /// assert_eq!(stockfish.get_move_history(), ["e2e4", "e7e5", "g1f3"]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn get_move_history(&self) -> &[String] {
+        &self.moves
+    }
+
+    /// Returns the FEN of the root position, from which the moves of
+    /// [`Stockfish::get_move_history`] were played. This is the standard starting
+    /// position unless another one was set through [`Stockfish::set_fen_position`].
+    #[must_use]
+    pub fn get_root_fen(&self) -> &str {
+        self.root_fen.as_deref().unwrap_or(STARTING_FEN)
+    }
+
+    /// Returns the current chess position, as tracked by the wrapper. (See [`Position`].)
+    #[must_use]
+    pub fn get_position(&self) -> &Position {
+        &self.position
     }
 
     /// Makes Stockfish calculate to the depth that has been set. (The default
@@ -246,48 +488,249 @@ impl Stockfish {
     /// 
     /// # Example
     /// 
-    /// ```rust
+    /// ```no_run
+    /// # use stockfish::Stockfish;
+    /// # fn main() -> Result<(), stockfish::StockfishError> {
     /// let mut stockfish = Stockfish::new("stockfish.exe")?;
     /// 
     /// let engine_output = stockfish.go()?;
     /// println!("output from stockfish: {engine_output:?}");
+    /// # Ok(())
+    /// # }
     /// ```
     /// 
     /// # Errors
     /// 
-    /// Returns an [`io::Error`] if an error occurred while trying to
+    /// Returns a [`StockfishError`] if an error occurred while trying to
     /// communicate with the engine.
-    pub fn go(&mut self) -> io::Result<EngineOutput> {
-        let message = String::from("go depth ") + &self.depth.to_string();
-        self.uci_send(&message)?;
-        self.get_engine_output()
+    pub fn go(&mut self) -> Result<EngineOutput> {
+        self.go_with(&GoParams::new().depth(self.depth))
+    }
+
+    /// Makes Stockfish calculate within the limits described by the given [`GoParams`],
+    /// which may combine clock times, increments, `movestogo`, depth, nodes, mate, move time
+    /// and `searchmoves`. (The depth that has been set through [`Stockfish::set_depth`] is only
+    /// used if `params` sets none of depth, nodes, mate, move time or clock times, since the
+    /// search would otherwise never conclude.)
+    /// 
+    /// Once Stockfish has finished its calculations, this function should return
+    /// an [`EngineOutput`] describing the result of its calculations.
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// # use stockfish::{GoParams, Stockfish};
+    /// use std::time::Duration;
+    /// # fn main() -> Result<(), stockfish::StockfishError> {
+    /// let mut stockfish = Stockfish::new("stockfish.exe")?;
+    /// 
+    /// let params = GoParams::new()
+    ///     .nodes(1_000_000)
+    ///     .move_time(Duration::from_secs(2))
+    ///     .search_moves(&["e2e4", "d2d4"]);
+    /// let engine_output = stockfish.go_with(&params)?;
+    /// println!("output from stockfish: {engine_output:?}");
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// 
+    /// # Errors
+    /// 
+    /// Returns a [`StockfishError`] if an error occurred while trying to
+    /// communicate with the engine. Since an infinite or ponder search would never conclude
+    /// on its own, [`StockfishError::InvalidGoParams`] is returned if `params` is infinite or
+    /// in ponder mode; use [`Stockfish::start_search_with`] for such searches.
+    pub fn go_with(&mut self, params: &GoParams) -> Result<EngineOutput> {
+        if params.is_infinite() {
+            return Err(StockfishError::InvalidGoParams(
+                "an infinite search must be started through start_search_with".to_owned()
+            ));
+        }
+        if params.is_ponder() {
+            return Err(StockfishError::InvalidGoParams(
+                "a ponder search must be started through start_search_with".to_owned()
+            ));
+        }
+        if !params.is_restricted()
+            && let Some(engine_output) = self.book_output()?
+        {
+            return Ok(engine_output);
+        }
+        if !params.is_bounded() {
+            return self.search(&params.clone().depth(self.depth).to_string())?.engine_output();
+        }
+        self.search(&params.to_string())?.engine_output()
+    }
+
+    /// Makes Stockfish calculate to the depth that has been set, like [`Stockfish::go`],
+    /// but returns immediately with a [`SearchStream`]: an iterator which yields each update
+    /// from the engine while it searches, ending with its best move.
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// # use stockfish::{SearchEvent, Stockfish};
+    /// # fn main() -> Result<(), stockfish::StockfishError> {
+    /// let mut stockfish = Stockfish::new("stockfish.exe")?;
+    /// 
+    /// for event in stockfish.go_streaming()? {
+    ///     match event? {
+    ///         SearchEvent::Info(info) => {
+    ///             if let (Some(depth), Some(score)) = (info.depth(), info.score()) {
+    ///                 println!("depth {depth}: {score}");
+    ///             }
+    ///         }
+    ///         SearchEvent::BestMove(engine_output) => {
+    ///             println!("best move: {}", engine_output.best_move());
+    ///         }
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// 
+    /// # Errors
+    /// 
+    /// Returns a [`StockfishError`] if an error occurred while trying to
+    /// communicate with the engine. Errors that occur during the search are
+    /// yielded by the stream.
+    pub fn go_streaming(&mut self) -> Result<SearchStream<'_>> {
+        let params = GoParams::new().depth(self.depth);
+        let collector = self.begin_search(&params.to_string())?;
+        Ok(SearchStream::new(self, collector))
     }
 
     /// Makes Stockfish calculate for a specified amount of time. Blocks the calling thread
-    /// for the duration of the specified calculation time.
+    /// for the duration of the specified calculation time (or until Stockfish concludes its
+    /// calculations on its own, if that happens sooner.)
     ///
     /// Once Stockfish has finished its calculations, this function should return
     /// an [`EngineOutput`] describing the result of its calculations.
     /// 
     /// # Example
     /// 
-    /// ```rust
+    /// ```no_run
+    /// # use stockfish::Stockfish;
     /// use std::time::Duration;
+    /// # fn main() -> Result<(), stockfish::StockfishError> {
     /// let mut stockfish = Stockfish::new("stockfish.exe")?;
     /// 
     /// let engine_output = stockfish.go_for(Duration::from_millis(500))?;
     /// println!("output from stockfish: {engine_output:?}");
+    /// # Ok(())
+    /// # }
     /// ```
     /// 
     /// # Errors
     /// 
-    /// Returns an [`io::Error`] if an error occurred while trying to
+    /// Returns a [`StockfishError`] if an error occurred while trying to
     /// communicate with the engine.
-    pub fn go_for(&mut self, calculation_time: Duration) -> io::Result<EngineOutput> {
-        self.uci_send("go")?;
-        std::thread::sleep(calculation_time);
-        self.uci_send("stop")?;
-        self.get_engine_output()
+    pub fn go_for(&mut self, calculation_time: Duration) -> Result<EngineOutput> {
+        if let Some(engine_output) = self.book_output()? {
+            return Ok(engine_output);
+        }
+        let mut handle = self.start_infinite_search()?;
+        if handle.wait_timeout(calculation_time)?.is_none() {
+            handle.stop()?;
+        }
+        handle.wait()
+    }
+
+    /// Makes Stockfish start calculating to the depth that has been set, like
+    /// [`Stockfish::go`], but returns immediately with a [`SearchHandle`] instead of
+    /// blocking until the search has concluded.
+    /// 
+    /// The handle may be polled for the latest output, told to stop the search,
+    /// or waited upon (with or without a timeout.)
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// # use stockfish::Stockfish;
+    /// use std::time::Duration;
+    /// # fn main() -> Result<(), stockfish::StockfishError> {
+    /// let mut stockfish = Stockfish::new("stockfish.exe")?;
+    /// stockfish.set_depth(30);
+    /// 
+    /// let mut handle = stockfish.start_search()?;
+    /// while !handle.poll()? {
+    ///     if let Some(info) = handle.latest_info() {
+    ///         println!("depth {:?}: {:?}", info.depth(), info.score());
+    ///     }
+    ///     std::thread::sleep(Duration::from_millis(100));
+    /// }
+    /// println!("best move: {}", handle.wait()?.best_move());
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// 
+    /// # Errors
+    /// 
+    /// Returns a [`StockfishError`] if an error occurred while trying to
+    /// communicate with the engine.
+    pub fn start_search(&mut self) -> Result<SearchHandle<'_>> {
+        self.start_search_with(&GoParams::new().depth(self.depth))
+    }
+
+    /// Makes Stockfish start calculating within the limits described by the given
+    /// [`GoParams`], returning a [`SearchHandle`] immediately (as with [`Stockfish::start_search`].)
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// # use stockfish::{GoParams, Stockfish};
+    /// use std::time::Duration;
+    /// # fn main() -> Result<(), stockfish::StockfishError> {
+    /// let mut stockfish = Stockfish::new("stockfish.exe")?;
+    /// 
+    /// let mut handle = stockfish.start_search_with(&GoParams::new().mate(5))?;
+    /// if handle.wait_timeout(Duration::from_secs(10))?.is_none() {
+    ///     handle.stop()?;
+    /// }
+    /// let engine_output = handle.wait()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// 
+    /// # Errors
+    /// 
+    /// Returns a [`StockfishError`] if an error occurred while trying to
+    /// communicate with the engine.
+    pub fn start_search_with(&mut self, params: &GoParams) -> Result<SearchHandle<'_>> {
+        let collector = self.begin_search(&params.to_string())?;
+        Ok(SearchHandle::new(self, collector))
+    }
+
+    /// Makes Stockfish start calculating without any limit (`go infinite`), returning
+    /// a [`SearchHandle`] immediately. The search only concludes once it is stopped
+    /// through [`SearchHandle::stop`] or a [`SearchStopper`](crate::SearchStopper).
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// # use stockfish::Stockfish;
+    /// use std::time::Duration;
+    /// # fn main() -> Result<(), stockfish::StockfishError> {
+    /// let mut stockfish = Stockfish::new("stockfish.exe")?;
+    /// 
+    /// let mut handle = stockfish.start_infinite_search()?;
+    /// let stopper = handle.stopper();
+    /// std::thread::spawn(move || {
+    ///     std::thread::sleep(Duration::from_secs(3));
+    ///     stopper.stop()
+    /// });
+    /// 
+    /// let engine_output = handle.wait()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// 
+    /// # Errors
+    /// 
+    /// Returns a [`StockfishError`] if an error occurred while trying to
+    /// communicate with the engine.
+    pub fn start_infinite_search(&mut self) -> Result<SearchHandle<'_>> {
+        self.start_search_with(&GoParams::new().infinite())
     }
 
     /// Makes Stockfish calculate for a variable time based on the times given as parameters.
@@ -300,7 +743,9 @@ impl Stockfish {
     /// 
     /// # Example
     /// 
-    /// ```rust
+    /// ```no_run
+    /// # use stockfish::Stockfish;
+    /// # fn main() -> Result<(), stockfish::StockfishError> {
     /// let mut stockfish = Stockfish::new("stockfish.exe")?;
     /// 
     /// let engine_output = stockfish.go_based_on_times(
@@ -308,23 +753,24 @@ impl Stockfish {
     ///     Some(55_000), // Black has 55 seconds
     /// )?;
     /// println!("output from stockfish: {engine_output:?}");
+    /// # Ok(())
+    /// # }
     /// ```
     /// 
     /// # Errors
     /// 
-    /// Returns an [`io::Error`] if an error occurred while trying to
+    /// Returns a [`StockfishError`] if an error occurred while trying to
     /// communicate with the engine.
-    pub fn go_based_on_times(&mut self, white_time: Option<u32>, black_time: Option<u32>) -> io::Result<EngineOutput> {
-        let mut message = String::from("go");
+    pub fn go_based_on_times(&mut self, white_time: Option<u32>, black_time: Option<u32>) -> Result<EngineOutput> {
+        let mut params = GoParams::new();
         if let Some(time) = white_time {
-            message += &format!(" wtime {time}");
+            params = params.white_time(Duration::from_millis(time.into()));
         }
         if let Some(time) = black_time {
-            message += &format!(" btime {time}");
+            params = params.black_time(Duration::from_millis(time.into()));
         }
 
-        self.uci_send(&message)?;
-        self.get_engine_output()
+        self.go_with(&params)
     }
 
     /// Configures the depth to which Stockfish will calculate. When methods like `go`
@@ -333,113 +779,315 @@ impl Stockfish {
     /// 
     /// # Example
     /// 
-    /// ```rust
+    /// ```no_run
+    /// # use stockfish::Stockfish;
+    /// # fn main() -> Result<(), stockfish::StockfishError> {
     /// let mut stockfish = Stockfish::new("stockfish.exe")?;
-    /// stockfish.set_depth(20)?;
+    /// stockfish.set_depth(20);
     /// let engine_output = stockfish.go()?; // Stockfish will calculate to the newly set depth
     /// println!("output from stockfish: {engine_output:?}");
+    /// # Ok(())
+    /// # }
     /// ```
-    /// 
-    /// # Errors
-    /// 
-    /// Returns an [`io::Error`] if an error occurred while trying to
-    /// communicate with the engine. 
     pub fn set_depth(&mut self, depth: u32) {
         self.depth = depth;
     }
 
-    /// This method is meant to only be called after Stockfish has received
-    /// a command for calculating a position.
-    /// Reads the lines outputted by the Stockfish process and returns an [`EngineOutput`]
-    /// value describing Stockfish's evaluation and its chosen best move.
-    fn get_engine_output(&mut self) -> io::Result<EngineOutput> {
-        let fen = self.get_fen()?;
+    /// Sets an opening book (or removes it, given [`None`].) While the book holds moves for
+    /// the current position, [`Stockfish::go`], [`Stockfish::go_with`], [`Stockfish::go_for`],
+    /// [`Stockfish::go_based_on_times`] and [`Stockfish::go_and_ponder`] return one of them,
+    /// chosen as per `selection`, without searching; see [`EngineOutput::is_book_move`].
+    /// (The book isn't consulted when [`GoParams`] restrict the search to some moves, or
+    /// look for a mate.)
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// # use stockfish::{BookSelection, PolyglotBook, Stockfish};
+    /// # fn main() -> Result<(), stockfish::StockfishError> {
+    /// let mut stockfish = Stockfish::new("stockfish.exe")?;
+    /// stockfish.set_book(Some(PolyglotBook::open("performance.bin")?), BookSelection::WeightedRandom);
+    /// 
+    /// let engine_output = stockfish.go()?;
+    /// if engine_output.is_book_move() {
+    ///     println!("book move: {}", engine_output.best_move());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_book(&mut self, book: Option<PolyglotBook>, selection: BookSelection) {
+        self.book = book.map(|book| (book, selection));
+    }
+
+    /// Makes Stockfish calculate to the depth that has been set, considering the given
+    /// number of candidate lines at once (by setting the `"MultiPV"` option, which stays set
+    /// for later searches.)
+    /// 
+    /// Once Stockfish has finished its calculations, this function returns the ranked
+    /// [`PvLine`]s of the final iteration of the search, best line first. Fewer lines are
+    /// returned if the position has fewer legal moves than requested.
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// # use stockfish::Stockfish;
+    /// # fn main() -> Result<(), stockfish::StockfishError> {
+    /// let mut stockfish = Stockfish::new("stockfish.exe")?;
+    /// 
+    /// for line in stockfish.go_multipv(3)? {
+    ///     println!("#{}: {} ({})", line.rank(), line.pv().join(" "), line.eval());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// 
+    /// # Errors
+    /// 
+    /// Returns a [`StockfishError`] if the number of lines is outside of the range accepted
+    /// by the engine, or if an error occurred while trying to communicate with the engine.
+    pub fn go_multipv(&mut self, lines: u32) -> Result<Vec<PvLine>> {
+        if self.get_option_value("MultiPV") != Some(&OptionValue::Spin(i64::from(lines))) {
+            self.set_option("MultiPV", &lines.to_string())?;
+        }
+
+        let params = GoParams::new().depth(self.depth);
+        Ok(self.search(&params.to_string())?.pv_lines())
+    }
 
-        // The output from stockfish normally displays the value of the evaluation score
-        // relative to the player with the current move. Use a multiplier to flip it such that
-        // the score is not relative to the player with the current move.
-        let color_multiplier = if fen.contains('w') {1} else {-1};
+    /// Sends a command for calculating the current position, then reads the engine's
+    /// output until the search has concluded.
+    fn search(&mut self, command: &str) -> Result<SearchOutcome> {
+        let collector = self.begin_search(command)?;
+        self.finish_search(collector)
+    }
 
-        let mut previous_line: Option<String> = None;
+    /// Prepares for reading the output of a search, then sends the given command
+    /// for calculating the current position.
+    fn begin_search(&mut self, command: &str) -> Result<SearchCollector> {
+        self.stop_pondering()?;
+        self.uci_send(command)?;
+        Ok(SearchCollector::new(color_multiplier(self.position.side_to_move())))
+    }
 
+    /// Reads the lines outputted by the Stockfish process up to and including `bestmove`.
+    /// If this fails before `bestmove`, the search is stopped and its remaining output is
+    /// discarded, so that it can't be mistaken for the output of the next command.
+    fn finish_search(&mut self, mut collector: SearchCollector) -> Result<SearchOutcome> {
         loop {
-            let line = self.read_line();
-            let mut segments = line.split(' ');
-            let first_segment = segments.next()
-                .expect("should be able to get first segment");
-            if first_segment != "bestmove" {
-                previous_line = Some(line);
-                continue;
+            let line = match self.read_line() {
+                Ok(line) => line,
+                Err(error) => return Err(self.fail_search(error)),
+            };
+            match collector.handle_line(&line) {
+                Ok(Some(SearchUpdate::BestMove(outcome))) => return Ok(outcome),
+                Ok(_) => {}
+                // The search is over, even though its result couldn't be read
+                Err(error) if line.starts_with("bestmove") => return Err(error),
+                Err(error) => return Err(self.fail_search(error)),
             }
-            
-            let previous_line = previous_line.unwrap();
-            let previous_segments: Vec<&str> = previous_line.split(' ').collect();
-
-            let mut score_type = None;
-            let mut score_value: Option<i32> = None;
-
-            let mut depth: Option<u32> = None;
-
-            for (i, segment) in previous_segments.iter().enumerate() {
-                if *segment == "depth" {
-                    depth = Some(
-                        previous_segments[i + 1].parse()
-                        .expect("should be able to parse stockfish info line")
-                    );
-                } else if *segment == "score" {
-                    score_type = Some(previous_segments[i + 1]);
-                    score_value = Some(
-                        previous_segments[i + 2].parse::<i32>()
-                            .expect("should be able to parse stockfish info line")
-                            * color_multiplier);
-                }
+        }
+    }
 
-                if depth.is_some() && score_type.is_some() {
-                    break;
-                }
+    /// Stops a search that failed with the given error, and tries to discard its remaining
+    /// output right away. (If that output doesn't arrive in time either, it is discarded
+    /// before the next command that awaits a response.)
+    fn fail_search(&mut self, error: StockfishError) -> StockfishError {
+        self.abandon_search();
+        let _ = self.discard_stale_responses(None);
+        error
+    }
+
+    /// Stops the running search without waiting for its `bestmove`, which is instead
+    /// discarded before the next command that awaits a response.
+    pub(crate) fn abandon_search(&mut self) {
+        if self.uci_send("stop").is_ok() {
+            self.stale_responses.push("bestmove");
+        }
+    }
+
+    /// Reads and discards the engine's output until every stale response has been read,
+    /// waiting no later than the given deadline.
+    fn discard_stale_responses(&mut self, deadline: Option<Instant>) -> Result<()> {
+        while !self.stale_responses.is_empty() {
+            let line = self.read_line_by(deadline)?;
+            // An engine may answer "isready" before it outputs the "bestmove" of a stopped search
+            if let Some(index) = self.stale_responses.iter().position(|response| line.starts_with(response)) {
+                self.stale_responses.remove(index);
             }
+        }
+        Ok(())
+    }
 
-            let score_type = score_type.expect("info line should have score type");
-            let score_value = score_value.expect("info line should have score value");
-            let depth = depth.expect("info line should have score value");
+    /// Makes Stockfish calculate to the depth that has been set, like [`Stockfish::go`], and
+    /// then plays its best move on the board. If Stockfish suggests a move that it expects the
+    /// opponent to reply with, Stockfish then starts pondering on that reply in the background
+    /// (enabling the `"Ponder"` option if needed.)
+    /// 
+    /// The opponent's actual reply should then be given to [`Stockfish::play_opponent_move`],
+    /// which either lets the ongoing search continue (on a `"ponderhit"`) or restarts it.
+    /// If a search is still running from a `"ponderhit"`, this method waits for its result
+    /// instead of starting a new search.
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// # use stockfish::Stockfish;
+    /// # fn main() -> Result<(), stockfish::StockfishError> {
+    /// # fn read_opponent_move() -> String { String::new() }
+    /// let mut stockfish = Stockfish::new("stockfish.exe")?;
+    /// stockfish.setup_for_new_game()?;
+    /// 
+    /// loop {
+    ///     let engine_output = stockfish.go_and_ponder()?; // Stockfish now ponders
+    ///     println!("Stockfish plays {}", engine_output.best_move());
+    /// 
+    ///     let opponent_move = read_opponent_move();
+    ///     stockfish.play_opponent_move(&opponent_move)?;
+    /// }
+    /// # }
+    /// ```
+    /// 
+    /// # Errors
+    /// 
+    /// Returns a [`StockfishError`] if an error occurred while trying to
+    /// communicate with the engine.
+    pub fn go_and_ponder(&mut self) -> Result<EngineOutput> {
+        // A search started through a "ponderhit" is only possible once out of book
+        if !matches!(self.ponder_state, Some(PonderState::Searching { .. }))
+            && let Some(engine_output) = self.book_output()?
+        {
+            self.push_move(engine_output.best_move())?;
+            self.send_position()?;
+            return Ok(engine_output);
+        }
+        let outcome = match self.ponder_state.take() {
+            Some(PonderState::Searching { collector }) => self.finish_search(collector)?,
+            ponder_state => {
+                self.ponder_state = ponder_state;
+                let params = GoParams::new().depth(self.depth);
+                self.search(&params.to_string())?
+            }
+        };
+        // After two plies, the side to move is the same as in the searched position
+        let collector = SearchCollector::new(outcome.color_multiplier());
+        let engine_output = outcome.engine_output()?;
+        if engine_output.best_move() == "(none)" {
+            // There are no moves to play in the searched position
+            return Ok(engine_output);
+        }
+        self.push_move(engine_output.best_move())?;
 
-            let score_type = EvalType::from_descriptor(score_type);
-            let eval = EngineEval::new(score_type, score_value);
+        let Some(expected_move) = engine_output.pondered_move().clone() else {
+            self.send_position()?;
+            return Ok(engine_output);
+        };
 
-            let best_move = segments.next()
-                .expect("should be able to parse stockfish bestmove line")
-                .to_owned();
+        if self.get_option_value("Ponder") == Some(&OptionValue::Check(false)) {
+            self.set_option("Ponder", "true")?;
+        }
 
-            let pondered_move = if segments.next().is_some_and(|str| str == "ponder") {
-                Some(segments.next()
-                    .expect("should be able to parse stockfish ponder entry")
-                    .to_owned())
-            } else {
-                None
-            };
+        // The history includes the engine's move, so the position command already lists moves
+        let position = position_command(self.root_fen.as_deref(), &self.moves);
+        self.uci_send(&format!("{position} {expected_move}"))?;
+        let message = String::from("go ponder depth ") + &self.depth.to_string();
+        self.uci_send(&message)?;
+
+        self.ponder_state = Some(PonderState::Pondering { expected_move, collector });
+        Ok(engine_output)
+    }
 
-            let output = EngineOutput::new(eval, best_move, pondered_move, depth);
-            return Ok(output);
+    /// Plays the opponent's reply to the move returned by [`Stockfish::go_and_ponder`].
+    /// 
+    /// If Stockfish was pondering on this very move, it is told so through `"ponderhit"`,
+    /// and the search continues as a regular search; its result is then returned by the
+    /// next call to [`Stockfish::go_and_ponder`]. Otherwise, the pondering is stopped and
+    /// the move is played on the board, as with [`Stockfish::play_move`]. (The move is read
+    /// as with [`Stockfish::play_move`] too.)
+    /// 
+    /// # Errors
+    /// 
+    /// Returns a [`StockfishError`] if an error occurred while trying to
+    /// communicate with the engine, or if the move doesn't denote exactly one legal move.
+    pub fn play_opponent_move(&mut self, move_str: &str) -> Result<()> {
+        // Check the move before the ongoing search is disturbed
+        let move_str = &self.position.parse_move(move_str)?.to_string();
+
+        match self.ponder_state.take() {
+            Some(PonderState::Pondering { expected_move, collector }) if expected_move == *move_str => {
+                self.uci_send("ponderhit")?;
+                self.push_move(&expected_move)?;
+                self.ponder_state = Some(PonderState::Searching { collector });
+                Ok(())
+            }
+            Some(PonderState::Pondering { collector, .. }) => {
+                self.uci_send("stop")?;
+                self.finish_search(collector)?;
+                self.push_move(move_str)?;
+                self.send_position()
+            }
+            ponder_state => {
+                self.ponder_state = ponder_state;
+                self.play_move(move_str)
+            }
         }
     }
 
+    /// Stops Stockfish from pondering (or from finishing a search that was started through
+    /// a `"ponderhit"`), discarding the result. Does nothing if Stockfish isn't pondering.
+    /// 
+    /// This is done automatically by the methods that change the position or start a new
+    /// search. When Stockfish was pondering on the opponent's expected reply, that reply
+    /// remains played on the board. The leftover output of a search that previously failed
+    /// (e.g., with [`StockfishError::Timeout`]) is discarded here as well.
+    /// 
+    /// # Errors
+    /// 
+    /// Returns a [`StockfishError`] if an error occurred while trying to
+    /// communicate with the engine.
+    pub fn stop_pondering(&mut self) -> Result<()> {
+        self.discard_stale_responses(None)?;
+        let collector = match self.ponder_state.take() {
+            Some(PonderState::Pondering { expected_move, collector }) => {
+                // The engine's board already includes the expected reply
+                self.push_move(&expected_move)?;
+                collector
+            }
+            Some(PonderState::Searching { collector }) => collector,
+            None => return Ok(()),
+        };
+        self.uci_send("stop")?;
+        self.finish_search(collector)?;
+        Ok(())
+    }
+
+    /// Returns `true` if Stockfish is pondering, or is finishing a search that was started
+    /// through a `"ponderhit"`.
+    #[must_use]
+    pub fn is_pondering(&self) -> bool {
+        self.ponder_state.is_some()
+    }
+
     /// Returns a string showing a visual display of the current chess position
     /// in which Stockfish is playing.
     /// 
     /// # Example
     /// 
-    /// ```rust
+    /// ```no_run
+    /// # use stockfish::Stockfish;
+    /// # fn main() -> Result<(), stockfish::StockfishError> {
     /// let mut stockfish = Stockfish::new("stockfish.exe")?;
     /// stockfish.play_move("d2d4")?;
     /// 
     /// let board = stockfish.get_board_display()?;
     /// println!("board: {board}");
+    /// # Ok(())
+    /// # }
     /// ```
     /// 
     /// # Illustration
     /// Example of the output from Stockfish:
     /// 
-    /// ```
+    /// ```text
     /// +---+---+---+---+---+---+---+---+
     /// | r | n | b | q | k | b | n | r | 8
     /// +---+---+---+---+---+---+---+---+
@@ -462,22 +1110,27 @@ impl Stockfish {
     /// 
     /// # Errors
     /// 
-    /// Returns an [`io::Error`] if an error occurred while trying to
+    /// Returns a [`StockfishError`] if an error occurred while trying to
     /// communicate with the engine.
-    pub fn get_board_display(&mut self) -> io::Result<String> {
+    pub fn get_board_display(&mut self) -> Result<String> {
+        self.stop_pondering()?;
         self.uci_send("d")?;
 
         let mut lines: Vec<String> = Vec::with_capacity(20);
 
         loop {
-            let line = self.read_line();
+            let line = match self.read_line() {
+                Ok(line) => line,
+                Err(error) => {
+                    self.stale_responses.push("Fen:");
+                    return Err(error);
+                }
+            };
             if line.is_empty() {
                 continue;
             }
 
-            let first_segment = line.split(' ').next()
-                .expect("non-empty line should have segment");
-            if first_segment == "Fen:" {
+            if line.starts_with("Fen:") {
                 break;
             }
             lines.push(line);
@@ -489,7 +1142,9 @@ impl Stockfish {
     /// 
     /// # Example
     /// 
-    /// ```rust
+    /// ```no_run
+    /// # use stockfish::Stockfish;
+    /// # fn main() -> Result<(), stockfish::StockfishError> {
     /// let mut stockfish = Stockfish::new("stockfish.exe")?;
     /// 
     /// stockfish.play_move("d2d4")?;
@@ -497,13 +1152,15 @@ impl Stockfish {
     /// 
     /// stockfish.play_move("d7d5")?;
     /// stockfish.print_board()?;
+    /// # Ok(())
+    /// # }
     /// ```
     /// 
     /// # Errors
     /// 
-    /// Returns an [`io::Error`] if an error occurred while trying to
+    /// Returns a [`StockfishError`] if an error occurred while trying to
     /// communicate with the engine. 
-    pub fn print_board(&mut self) -> io::Result<()> {
+    pub fn print_board(&mut self) -> Result<()> {
         let board_display = self.get_board_display()?;
         println!("{board_display}");
         Ok(())
@@ -512,35 +1169,147 @@ impl Stockfish {
     /// Sets a UCI option for the Stockfish engine. This is used for changing the engine's
     /// internal parameters.
     /// 
+    /// The value is checked against the type and range that the engine declared for the
+    /// option before being sent, and is remembered so that it may later be queried through
+    /// [`Stockfish::get_option_value`].
+    /// 
     /// # Example
     /// 
-    /// ```rust
+    /// ```no_run
+    /// # use stockfish::Stockfish;
+    /// # fn main() -> Result<(), stockfish::StockfishError> {
     /// let mut stockfish = Stockfish::new("stockfish.exe")?;
     /// stockfish.set_option("Move Overhead", "20")?;
+    /// 
+    /// // Rejected, since the engine declares no such option
+    /// assert!(stockfish.set_option("Move Overheaad", "20").is_err());
+    /// # Ok(())
+    /// # }
     /// ```
-    /// The following is a listing of some of the possible options and their default values.
-    /// (Note: these may be subject to change, and may not be universal.)
-    /// - `"Threads"`: 1,
-    /// - `"Hash"`: 16,
-    /// - `"Debug Log File"`: "",
-    /// - `"Contempt"`: 0,
-    /// - `"Min Split Depth"`: 0,
-    /// - `"Ponder"`: "false",
-    /// - `"MultiPV"`: 1,
-    /// - `"Move Overhead"`: 10,
-    /// - `"Minimum Thinking Time"`: 20,
-    /// - `"Slow Mover"`: 100,
-    /// - `"UCI_Chess960"`: "false",
-    /// - `"UCI_LimitStrength"`: "false",
-    /// - `"UCI_Elo"`: 1350,
-    /// - `"Skill Level"`: 20,
-    /// 
-    /// # Errors
-    /// 
-    /// Returns an [`io::Error`] if an error occurred while trying to
-    /// communicate with the engine. 
-    pub fn set_option(&mut self, option_name: &str, option_value: &str) -> io::Result<()> {
-        self.uci_send(&format!("setoption name {option_name} value {option_value}"))
+    /// 
+    /// The options supported by the engine, along with their default values,
+    /// may be listed through [`Stockfish::get_options`].
+    /// 
+    /// # Errors
+    /// 
+    /// Returns [`StockfishError::UnknownOption`] if the engine didn't declare an option
+    /// with the given name, [`StockfishError::InvalidOptionValue`] if the value doesn't
+    /// suit the option, or another [`StockfishError`] if an error occurred while trying to
+    /// communicate with the engine.
+    pub fn set_option(&mut self, option_name: &str, option_value: &str) -> Result<()> {
+        let value = self.get_declared_option(option_name)?.parse_value(option_value)?;
+        self.send_option_value(option_name, value)
+    }
+
+    /// Presses a button option, such as `"Clear Hash"`, which makes the engine perform
+    /// the associated action.
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// # use stockfish::Stockfish;
+    /// # fn main() -> Result<(), stockfish::StockfishError> {
+    /// let mut stockfish = Stockfish::new("stockfish.exe")?;
+    /// stockfish.press_button("Clear Hash")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// 
+    /// # Errors
+    /// 
+    /// Returns [`StockfishError::UnknownOption`] if the engine didn't declare an option
+    /// with the given name, [`StockfishError::InvalidOptionValue`] if the option isn't a
+    /// button, or another [`StockfishError`] if an error occurred while trying to
+    /// communicate with the engine.
+    pub fn press_button(&mut self, option_name: &str) -> Result<()> {
+        let option = self.get_declared_option(option_name)?;
+        if *option.option_type() != OptionType::Button {
+            return Err(StockfishError::InvalidOptionValue {
+                name: option.name().to_owned(),
+                value: String::new(),
+                reason: format!("expected a button option, but it is of type {}", option.option_type()),
+            });
+        }
+        let message = format!("setoption name {}", option.name());
+        self.uci_send(&message)
+    }
+
+    /// Clears Stockfish's hashtable/transposition table, by pressing the
+    /// `"Clear Hash"` button option.
+    /// 
+    /// # Errors
+    /// 
+    /// Returns a [`StockfishError`] if the engine has no `"Clear Hash"` option or if
+    /// an error occurred while trying to communicate with the engine.
+    pub fn clear_hash(&mut self) -> Result<()> {
+        self.press_button("Clear Hash")
+    }
+
+    /// Returns the value that an option currently holds. This is the option's default
+    /// value, unless it has been changed through this wrapper.
+    /// 
+    /// Returns [`None`] if the engine declared no such option, or if the option is a button.
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// # use stockfish::{OptionValue, Stockfish};
+    /// # fn main() -> Result<(), stockfish::StockfishError> {
+    /// let mut stockfish = Stockfish::new("stockfish.exe")?;
+    /// stockfish.set_hash(64)?;
+    /// assert_eq!(stockfish.get_option_value("Hash"), Some(&OptionValue::Spin(64)));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn get_option_value(&self, option_name: &str) -> Option<&OptionValue> {
+        self.options.get(option_name)?.value()
+    }
+
+    /// Resets an option to the default value that the engine declared for it.
+    /// 
+    /// # Errors
+    /// 
+    /// Returns [`StockfishError::UnknownOption`] if the engine didn't declare an option
+    /// with the given name, or another [`StockfishError`] if an error occurred while trying
+    /// to communicate with the engine.
+    pub fn reset_option(&mut self, option_name: &str) -> Result<()> {
+        match self.get_declared_option(option_name)?.default_value() {
+            Some(value) => self.send_option_value(option_name, value),
+            None => Ok(()),
+        }
+    }
+
+    /// Resets every option that no longer holds its default value.
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// # use stockfish::Stockfish;
+    /// # fn main() -> Result<(), stockfish::StockfishError> {
+    /// let mut stockfish = Stockfish::new("stockfish.exe")?;
+    /// stockfish.set_skill_level(5)?;
+    /// stockfish.reset_options()?; // Back to full strength
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// 
+    /// # Errors
+    /// 
+    /// Returns a [`StockfishError`] if an error occurred while trying to
+    /// communicate with the engine.
+    pub fn reset_options(&mut self) -> Result<()> {
+        let changed: Vec<(String, OptionValue)> = self.options.iter()
+            .filter_map(|option| {
+                let default = option.default_value()?;
+                (option.value() != Some(&default)).then(|| (option.name().to_owned(), default))
+            })
+            .collect();
+
+        for (name, value) in changed {
+            self.send_option_value(&name, value)?;
+        }
+        Ok(())
     }
 
     /// Sets the size of Stockfish's hashtable/transposition table.
@@ -549,16 +1318,20 @@ impl Stockfish {
     /// 
     /// # Example
     /// 
-    /// ```rust
+    /// ```no_run
+    /// # use stockfish::Stockfish;
+    /// # fn main() -> Result<(), stockfish::StockfishError> {
     /// let mut stockfish = Stockfish::new("stockfish.exe")?;
     /// stockfish.set_hash(64)?;
+    /// # Ok(())
+    /// # }
     /// ```
     /// 
     /// # Errors
     /// 
-    /// Returns an [`io::Error`] if an error occurred while trying to
-    /// communicate with the engine. 
-    pub fn set_hash(&mut self, hash: u32) -> io::Result<()> {
+    /// Returns a [`StockfishError`] if the size is outside of the range accepted by the
+    /// engine, or if an error occurred while trying to communicate with the engine.
+    pub fn set_hash(&mut self, hash: u32) -> Result<()> {
         self.set_option("Hash", &hash.to_string())
     }
 
@@ -568,17 +1341,21 @@ impl Stockfish {
     /// 
     /// # Example
     /// 
-    /// ```rust
+    /// ```no_run
+    /// # use stockfish::Stockfish;
+    /// # fn main() -> Result<(), stockfish::StockfishError> {
     /// 
     /// let mut stockfish = Stockfish::new("stockfish.exe")?;
     /// stockfish.set_threads(16)?;
+    /// # Ok(())
+    /// # }
     /// ```
     /// 
     /// # Errors
     /// 
-    /// Returns an [`io::Error`] if an error occurred while trying to
-    /// communicate with the engine. 
-    pub fn set_threads(&mut self, threads: u32) -> io::Result<()> {
+    /// Returns a [`StockfishError`] if the thread count is outside of the range accepted by
+    /// the engine, or if an error occurred while trying to communicate with the engine.
+    pub fn set_threads(&mut self, threads: u32) -> Result<()> {
         self.set_option("Threads", &threads.to_string())
     }
 
@@ -589,7 +1366,9 @@ impl Stockfish {
     /// 
     /// # Example
     /// 
-    /// ```rust
+    /// ```no_run
+    /// # use stockfish::Stockfish;
+    /// # fn main() -> Result<(), stockfish::StockfishError> {
     /// let mut stockfish = Stockfish::new("stockfish.exe")?;
     /// stockfish.set_fen_position("r1bqkbnr/ppp1pppp/2np4/8/8/3P4/PPPBPPPP/RN1QKBNR w KQkq - 0 1")?;
     /// 
@@ -598,15 +1377,20 @@ impl Stockfish {
     /// stockfish.set_elo(1450)?;
     /// 
     /// let engine_output = stockfish.go()?;
+    /// # Ok(())
+    /// # }
     /// ```
     /// 
     /// # Errors
     /// 
-    /// Returns an [`io::Error`] if an error occurred while trying to
-    /// communicate with the engine. 
-    pub fn set_elo(&mut self, elo: u32) -> io::Result<()> {
+    /// Returns a [`StockfishError`] if the elo is outside of the range accepted by the
+    /// engine (its `"UCI_Elo"` option), or if an error occurred while trying to
+    /// communicate with the engine.
+    pub fn set_elo(&mut self, elo: u32) -> Result<()> {
+        // Validate the elo before limiting the engine's strength
+        let elo = self.get_declared_option("UCI_Elo")?.parse_value(&elo.to_string())?;
         self.set_option("UCI_LimitStrength", "true")?;
-        self.set_option("Elo", &elo.to_string())
+        self.send_option_value("UCI_Elo", elo)
     }
 
     /// Sets the skill level at which Stockfish will aim to play. Skill level
@@ -617,7 +1401,9 @@ impl Stockfish {
     /// 
     /// # Example
     /// 
-    /// ```rust
+    /// ```no_run
+    /// # use stockfish::Stockfish;
+    /// # fn main() -> Result<(), stockfish::StockfishError> {
     /// let mut stockfish = Stockfish::new("stockfish.exe")?;
     /// stockfish.set_fen_position("r1bqkbnr/ppp1pppp/2np4/8/8/3P4/PPPBPPPP/RN1QKBNR w KQkq - 0 1")?;
     /// 
@@ -626,15 +1412,18 @@ impl Stockfish {
     /// stockfish.set_skill_level(16)?;
     /// 
     /// let engine_output = stockfish.go()?;
+    /// # Ok(())
+    /// # }
     /// ```
     /// 
     /// # Errors
     /// 
-    /// Returns an [`io::Error`] if an error occurred while trying to
-    /// communicate with the engine. 
-    pub fn set_skill_level(&mut self, skill_level: u32) -> io::Result<()> {
+    /// Returns a [`StockfishError`] if the skill level is outside of the range accepted by
+    /// the engine, or if an error occurred while trying to communicate with the engine.
+    pub fn set_skill_level(&mut self, skill_level: u32) -> Result<()> {
+        let skill_level = self.get_declared_option("Skill Level")?.parse_value(&skill_level.to_string())?;
         self.set_option("UCI_LimitStrength", "false")?;
-        self.set_option("Skill Level", &skill_level.to_string())
+        self.send_option_value("Skill Level", skill_level)
     }
 
     /// Returns a string representing the version of Stockfish being run.
@@ -645,24 +1434,65 @@ impl Stockfish {
         &self.version
     }
 
+    /// Returns the name that the engine identified itself with during the UCI
+    /// handshake (e.g., `"Stockfish 16.1"`.)
+    /// Returns [`None`] if the engine didn't send an `id name` line.
+    #[must_use]
+    pub fn get_engine_name(&self) -> &Option<String> {
+        &self.engine_name
+    }
+
+    /// Returns the author(s) that the engine declared during the UCI handshake.
+    /// Returns [`None`] if the engine didn't send an `id author` line.
+    #[must_use]
+    pub fn get_engine_author(&self) -> &Option<String> {
+        &self.engine_author
+    }
+
+    /// Returns the registry of [`EngineOptions`] that the engine declared during
+    /// the UCI handshake. This describes which options the engine actually supports,
+    /// along with their types, default values and limits. (Declarations that couldn't
+    /// be parsed are left out.)
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// # use stockfish::Stockfish;
+    /// # fn main() -> Result<(), stockfish::StockfishError> {
+    /// let stockfish = Stockfish::new("stockfish.exe")?;
+    /// for option in stockfish.get_options() {
+    ///     println!("{}: {:?}", option.name(), option.option_type());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn get_options(&self) -> &EngineOptions {
+        &self.options
+    }
+
     /// Sends the `"quit"` UCI command to the Stockfish process, whereupon it
     /// will attempt to quit the program as soon as possible.
     /// 
     /// # Example
     /// 
-    /// ```rust
+    /// ```no_run
+    /// # use stockfish::Stockfish;
+    /// # fn main() -> Result<(), stockfish::StockfishError> {
     /// let mut stockfish = Stockfish::new("stockfish.exe")?;
     /// stockfish.set_fen_position("rn1qkbnr/pbpppppp/1p6/8/8/1P2P3/PBPP1PPP/RN1QKBNR w KQkq - 0 1")?;
     /// let engine_output = stockfish.go()?;
     /// 
     /// stockfish.quit()?;
+    /// # Ok(())
+    /// # }
     /// ```
     /// 
     /// # Errors
     /// 
-    /// Returns an [`io::Error`] if an error occurred while trying to
+    /// Returns a [`StockfishError`] if an error occurred while trying to
     /// communicate with the engine. 
-    pub fn quit(&mut self) -> io::Result<()> {
+    pub fn quit(&mut self) -> Result<()> {
         self.uci_send("quit")
     }
 
@@ -674,15 +1504,280 @@ impl Stockfish {
     /// 
     /// # Errors
     /// 
-    /// Returns an [`io::Error`] if an error occurred while trying to
+    /// Returns a [`StockfishError`] if an error occurred while trying to
     /// communicate with the engine. 
-    pub fn uci_send(&mut self, command: &str) -> io::Result<()> {
-        self.interactive_process.send(command)?;
+    pub fn uci_send(&mut self, command: &str) -> Result<()> {
+        send_to_process(&self.interactive_process, command)?;
+        command.clone_into(&mut self.last_command);
         Ok(())
     }
 
+    /// Sets the maximum amount of time to wait for each line of output from the engine
+    /// while a command is awaiting a response. If the engine stays silent for longer than
+    /// this, the waiting method returns [`StockfishError::Timeout`] (or
+    /// [`StockfishError::Unresponsive`], if the watchdog is enabled.)
+    /// 
+    /// Since a searching engine regularly outputs `info` lines, this also bounds how long
+    /// a stalled search may block. By default, there is no timeout.
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// # use stockfish::Stockfish;
+    /// use std::time::Duration;
+    /// # fn main() -> Result<(), stockfish::StockfishError> {
+    /// let mut stockfish = Stockfish::new("stockfish.exe")?;
+    /// stockfish.set_response_timeout(Some(Duration::from_secs(10)));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_response_timeout(&mut self, timeout: Option<Duration>) {
+        self.response_timeout = timeout;
+    }
+
+    /// Sets the total amount of time that [`Stockfish::ensure_ready`] will wait for the
+    /// engine to reply with `"readyok"`. By default, there is no timeout.
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// # use stockfish::Stockfish;
+    /// use std::time::Duration;
+    /// # fn main() -> Result<(), stockfish::StockfishError> {
+    /// let mut stockfish = Stockfish::new("stockfish.exe")?;
+    /// stockfish.set_ensure_ready_timeout(Some(Duration::from_secs(2)));
+    /// stockfish.ensure_ready()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_ensure_ready_timeout(&mut self, timeout: Option<Duration>) {
+        self.ensure_ready_timeout = timeout;
+    }
+
+    /// Enables or disables the watchdog. When enabled, an engine that fails to respond
+    /// within one of the configured timeouts is killed, and the timed-out method returns
+    /// [`StockfishError::Unresponsive`]. Every later call then returns
+    /// [`StockfishError::EngineExited`]. The watchdog is disabled by default.
+    /// 
+    /// # Example
+    /// 
+    /// ```no_run
+    /// # use stockfish::{Stockfish, StockfishError};
+    /// use std::time::Duration;
+    /// # fn main() -> Result<(), StockfishError> {
+    /// let mut stockfish = Stockfish::new("stockfish.exe")?;
+    /// stockfish.set_response_timeout(Some(Duration::from_secs(10)));
+    /// stockfish.set_watchdog(true);
+    /// 
+    /// match stockfish.go() {
+    ///     Err(StockfishError::Unresponsive { .. }) => {
+    ///         // The process has been killed; start a new one
+    ///         stockfish = Stockfish::new("stockfish.exe")?;
+    ///     }
+    ///     result => println!("{:?}", result?),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_watchdog(&mut self, enabled: bool) {
+        self.watchdog = enabled;
+    }
+
+    /// Returns `false` if the engine process has been killed by the watchdog.
+    #[must_use]
+    pub fn is_running(&self) -> bool {
+        self.interactive_process.lock().unwrap_or_else(PoisonError::into_inner).is_some()
+    }
+
     /* Private Methods */
-    fn read_line(&mut self) -> String {
-        self.receiver.recv().expect("should be able to read from receiver")
+
+    /// Picks a move from the opening book for the current position, if there is one.
+    fn book_output(&mut self) -> Result<Option<EngineOutput>> {
+        if self.book.is_none() {
+            return Ok(None);
+        }
+        // The pondered reply, if any, becomes part of the position
+        self.stop_pondering()?;
+        let Some((book, selection)) = &self.book else {
+            return Ok(None);
+        };
+        let book_move = book.pick_move(&self.position, *selection);
+        Ok(book_move.map(|mv| EngineOutput::from_book(mv.to_string())))
+    }
+
+    /// Records a move on the board, without sending the new position to the engine.
+    fn push_move(&mut self, move_str: &str) -> Result<()> {
+        self.position.play_uci(move_str)?;
+        self.moves.push(move_str.to_owned());
+        Ok(())
+    }
+
+    fn send_position(&mut self) -> Result<()> {
+        let command = position_command(self.root_fen.as_deref(), &self.moves);
+        self.uci_send(&command)
+    }
+
+    fn get_declared_option(&self, option_name: &str) -> Result<&EngineOption> {
+        self.options.get(option_name)
+            .ok_or_else(|| StockfishError::UnknownOption(option_name.to_owned()))
+    }
+
+    /// Sends an already validated value for an option, and records it.
+    fn send_option_value(&mut self, option_name: &str, value: OptionValue) -> Result<()> {
+        let message = format!("setoption name {} value {value}", self.get_declared_option(option_name)?.name());
+        self.uci_send(&message)?;
+        if let Some(option) = self.options.get_mut(option_name) {
+            option.set_value(value);
+        }
+        Ok(())
+    }
+
+    /// Sends `"uci"` and records the `id` and `option` lines that precede `"uciok"`.
+    /// Any other output (such as the banner that Stockfish prints on startup) is ignored.
+    fn uci_handshake(&mut self) -> Result<()> {
+        self.uci_send("uci")?;
+        let deadline = Instant::now() + HANDSHAKE_TIMEOUT;
+        loop {
+            let line = self.read_line_by(Some(deadline))?;
+            if line == "uciok" {
+                break;
+            }
+
+            if let Some(name) = line.strip_prefix("id name ") {
+                self.engine_name = Some(name.to_owned());
+                self.version = name.split(' ').nth(1).map(ToString::to_string);
+            } else if let Some(author) = line.strip_prefix("id author ") {
+                self.engine_author = Some(author.to_owned());
+            } else if line.starts_with("option ")
+                // An unusual declaration that can't be parsed shouldn't prevent using the engine
+                && let Ok(option) = EngineOption::parse(&line)
+            {
+                self.options.push(option);
+            }
+        }
+        Ok(())
+    }
+    pub(crate) fn read_line(&mut self) -> Result<String> {
+        self.read_line_by(None)
+    }
+
+    /// Reads the next line from the engine if one arrives within the given duration.
+    /// Unlike with the response timeout, running out of time here isn't an error.
+    pub(crate) fn try_read_line(&mut self, timeout: Duration) -> Result<Option<String>> {
+        match self.receiver.recv_timeout(timeout) {
+            Ok(line) => Ok(Some(line?)),
+            Err(RecvTimeoutError::Timeout) => Ok(None),
+            Err(RecvTimeoutError::Disconnected) => Err(StockfishError::EngineExited),
+        }
+    }
+
+    pub(crate) fn shared_process(&self) -> SharedProcess {
+        Arc::clone(&self.interactive_process)
+    }
+
+    /// Records the depth, option values, timeouts, watchdog and opening book.
+    pub(crate) fn settings(&self) -> Settings {
+        Settings {
+            depth: self.depth,
+            option_values: self.options.iter()
+                .filter_map(|option| Some((option.name().to_owned(), option.value()?.clone())))
+                .collect(),
+            response_timeout: self.response_timeout,
+            ensure_ready_timeout: self.ensure_ready_timeout,
+            watchdog: self.watchdog,
+            book: self.book.clone(),
+        }
+    }
+
+    /// Restores the settings recorded by [`Stockfish::settings`], sending the values
+    /// of the options that have changed since.
+    pub(crate) fn restore_settings(&mut self, settings: &Settings) -> Result<()> {
+        self.depth = settings.depth;
+        self.response_timeout = settings.response_timeout;
+        self.ensure_ready_timeout = settings.ensure_ready_timeout;
+        self.watchdog = settings.watchdog;
+        self.book.clone_from(&settings.book);
+
+        for (name, value) in &settings.option_values {
+            if self.get_option_value(name) != Some(value) {
+                self.send_option_value(name, value.clone())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads the next line from the engine, waiting no longer than the response timeout
+    /// and no later than the given deadline.
+    fn read_line_by(&mut self, deadline: Option<Instant>) -> Result<String> {
+        let mut timeout = self.response_timeout;
+        if let Some(deadline) = deadline {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            timeout = Some(timeout.map_or(remaining, |timeout| timeout.min(remaining)));
+        }
+
+        let Some(timeout) = timeout else {
+            return match self.receiver.recv() {
+                Ok(line) => Ok(line?),
+                Err(_) => Err(StockfishError::EngineExited),
+            };
+        };
+
+        match self.receiver.recv_timeout(timeout) {
+            Ok(line) => Ok(line?),
+            Err(RecvTimeoutError::Disconnected) => Err(StockfishError::EngineExited),
+            Err(RecvTimeoutError::Timeout) => Err(self.handle_timeout(timeout)),
+        }
+    }
+
+    fn handle_timeout(&mut self, timeout: Duration) -> StockfishError {
+        let command = self.last_command.clone();
+        if !self.watchdog {
+            return StockfishError::Timeout { command, timeout };
+        }
+
+        self.kill();
+        StockfishError::Unresponsive { command, timeout }
     }
-}
\ No newline at end of file
+
+    /// Kills the engine process, unless that has already been done.
+    pub(crate) fn kill(&mut self) {
+        let process = self.interactive_process.lock().unwrap_or_else(PoisonError::into_inner).take();
+        if let Some(process) = process {
+            let mut child = process.close();
+            // The process may have exited on its own in the meantime
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+/// Builds the `position` command for the given root position (the standard starting
+/// position if [`None`]) followed by the given moves.
+pub(crate) fn position_command(root_fen: Option<&str>, moves: &[String]) -> String {
+    let mut command = match root_fen {
+        Some(fen) => format!("position fen {fen}"),
+        None => String::from("position startpos"),
+    };
+    if !moves.is_empty() {
+        command += " moves ";
+        command += &moves.join(" ");
+    }
+    command
+}
+
+/// Returns the multiplier that makes scores reported relative to the side to move in the
+/// given position relative to white instead.
+pub(crate) fn color_multiplier(side_to_move: Color) -> i32 {
+    match side_to_move {
+        Color::White => 1,
+        Color::Black => -1,
+    }
+}
+
+/// Sends a command to the engine process, unless it has been killed.
+pub(crate) fn send_to_process(process: &Mutex<Option<InteractiveProcess>>, command: &str) -> Result<()> {
+    let mut process = process.lock().unwrap_or_else(PoisonError::into_inner);
+    let process = process.as_mut().ok_or(StockfishError::EngineExited)?;
+    process.send(command)?;
+    Ok(())
+}
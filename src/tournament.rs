@@ -0,0 +1,806 @@
+use std::{
+    fmt,
+    fs::{self, File, OpenOptions},
+    io::Write,
+    path::Path,
+    sync::{Mutex, PoisonError},
+    thread,
+};
+
+use crate::elo;
+use crate::engine_match::{self, Adjudication, EngineConfig, GameRecord, GameResult, Termination, TimeControl};
+use crate::engine_pool::EnginePool;
+use crate::error::{Result, StockfishError};
+use crate::pgn::{PgnGame, PgnReader};
+use crate::position::Color;
+
+/// The tag under which the reason that a game ended is saved, as formatted by [`Termination`].
+const TERMINATION_TAG: &str = "TerminationDetails";
+
+/// Which of the engines of a [`Tournament`] play each other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TournamentFormat {
+    /// Every engine plays every other engine.
+    RoundRobin,
+    /// The first engine plays every other engine, which don't play each other.
+    Gauntlet,
+}
+
+impl fmt::Display for TournamentFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", match self {
+            TournamentFormat::RoundRobin => "round-robin",
+            TournamentFormat::Gauntlet => "gauntlet",
+        })
+    }
+}
+
+impl TournamentFormat {
+    /// The name of a tournament of this format, as in the `[Event]` tag of its games.
+    fn event(self) -> &'static str {
+        match self {
+            TournamentFormat::RoundRobin => "Round-robin tournament",
+            TournamentFormat::Gauntlet => "Gauntlet tournament",
+        }
+    }
+}
+
+/// A tournament between several engine configurations, e.g. to rank different skill
+/// levels, hash sizes or binaries against each other.
+///
+/// Each pairing of engines plays the same number of games, alternating colors, with each
+/// opening of the opening set played twice in a row, once with either engine as white. The
+/// games of all pairings are interleaved, so that the standings stay balanced while the
+/// tournament is underway. Several games may be played at once: every engine configuration
+/// then gets a pool of as many engine processes as there are games in progress.
+///
+/// If a state file is given, every finished game is appended to it as PGN, and running the
+/// tournament again resumes it from the games that the file holds. (A game that was cut short
+/// while being saved is removed from the file and played again.)
+///
+/// # Example
+///
+/// ```no_run
+/// use stockfish::{EngineConfig, Tournament, TournamentFormat};
+/// # fn main() -> Result<(), stockfish::StockfishError> {
+/// let engines = [5, 10, 15, 20]
+///     .map(|level| {
+///         EngineConfig::new(&format!("skill-{level}"), "stockfish.exe")
+///             .option("Skill Level", &level.to_string())
+///     })
+///     .to_vec();
+///
+/// let tournament = Tournament::new(engines, TournamentFormat::RoundRobin)
+///     .games_per_pairing(10)
+///     .concurrency(4)
+///     .state_file("skill-levels.pgn");
+/// let result = tournament.run_with(|result| {
+///     println!("{} of {} games played", result.games().len(), result.scheduled_games());
+/// })?;
+///
+/// println!("{result}");
+/// std::fs::write("skill-levels.json", result.to_json())?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tournament {
+    engines: Vec<EngineConfig>,
+    format: TournamentFormat,
+    games_per_pairing: u32,
+    time_control: TimeControl,
+    openings: Vec<PgnGame>,
+    adjudication: Adjudication,
+    concurrency: usize,
+    state_file: Option<String>,
+}
+
+impl Tournament {
+
+    /// Creates a tournament of the given format between engine configurations, in which
+    /// each pairing plays two games, one at a time, with the default [`TimeControl`], from
+    /// the starting position, and without adjudication.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are fewer than two engines, or if two of them have the same name.
+    #[must_use]
+    pub fn new(engines: Vec<EngineConfig>, format: TournamentFormat) -> Self {
+        assert!(engines.len() >= 2, "a tournament needs at least two engines");
+        for (index, engine) in engines.iter().enumerate() {
+            assert!(
+                engines[..index].iter().all(|other| other.name() != engine.name()),
+                "engine names should be unique, but {:?} appears twice", engine.name(),
+            );
+        }
+
+        Self {
+            engines,
+            format,
+            games_per_pairing: 2,
+            time_control: TimeControl::default(),
+            openings: Vec::new(),
+            adjudication: Adjudication::default(),
+            concurrency: 1,
+            state_file: None,
+        }
+    }
+
+    #[must_use]
+    pub fn games_per_pairing(mut self, games: u32) -> Self {
+        self.games_per_pairing = games;
+        self
+    }
+
+    #[must_use]
+    pub fn time_control(mut self, time_control: TimeControl) -> Self {
+        self.time_control = time_control;
+        self
+    }
+
+    /// Sets the openings from which the games start, each of which is played from its root
+    /// position through its mainline. (Their order is kept; shuffle them beforehand to vary it.)
+    #[must_use]
+    pub fn openings(mut self, openings: Vec<PgnGame>) -> Self {
+        self.openings = openings;
+        self
+    }
+
+    #[must_use]
+    pub fn adjudication(mut self, adjudication: Adjudication) -> Self {
+        self.adjudication = adjudication;
+        self
+    }
+
+    /// Sets the number of games played at once.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `games` is 0.
+    #[must_use]
+    pub fn concurrency(mut self, games: usize) -> Self {
+        assert!(games > 0, "a tournament needs to play at least one game at a time");
+        self.concurrency = games;
+        self
+    }
+
+    /// Sets the file to which finished games are appended as PGN, and from which the
+    /// tournament is resumed when it is run again. Each game is saved with its `[Round]`,
+    /// `[White]`, `[Black]` and `[TerminationDetails]` tags, which must match the schedule.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::fs;
+    /// use stockfish::{EngineConfig, StockfishError, Tournament, TournamentFormat};
+    /// # fn main() -> Result<(), StockfishError> {
+    /// let path = std::env::temp_dir().join("stockfish-doctest-tournament.pgn");
+    /// let path = path.to_str().unwrap();
+    /// let engines = ["a", "b", "c"].map(|name| EngineConfig::new(name, "no-such-engine")).to_vec();
+    /// let tournament = Tournament::new(engines, TournamentFormat::RoundRobin).state_file(path);
+    ///
+    /// // The pairings a-b, a-c and b-c play in turn, then again with colors reversed
+    /// let rounds = [("a", "b", "1-0"), ("a", "c", "1/2-1/2"), ("b", "c", "1-0"),
+    ///               ("b", "a", "0-1"), ("c", "a", "1/2-1/2"), ("c", "b", "1-0")];
+    /// let games: Vec<String> = rounds.iter().enumerate()
+    ///     .map(|(index, (white, black, result))| {
+    ///         let termination = if *result == "1/2-1/2" {"adjudicated as drawn"} else {"adjudicated as lost"};
+    ///         format!(
+    ///             "[Round \"{}\"]\n[White \"{white}\"]\n[Black \"{black}\"]\n[Result \"{result}\"]\n\
+    ///             [TerminationDetails \"{termination}\"]\n\n1. e4 e5 {result}\n\n", index + 1,
+    ///         )
+    ///     })
+    ///     .collect();
+    ///
+    /// // The last game was cut short: it is dropped, and its round is to be played again
+    /// fs::write(path, games[..5].concat() + &games[5][..40])?;
+    /// let Err(StockfishError::Io(_)) = tournament.run() else {
+    ///     panic!("the engines should be spawned to replay the last round");
+    /// };
+    /// assert_eq!(fs::read_to_string(path)?, games[..5].concat());
+    ///
+    /// fs::write(path, games.concat())?;
+    /// let result = tournament.run()?; // Nothing is left to play
+    /// assert!(result.is_complete());
+    /// let standings = result.standings();
+    /// let table: Vec<(&str, f64, f64)> = standings.iter()
+    ///     .map(|standing| (standing.name(), standing.points(), standing.sonneborn_berger()))
+    ///     .collect();
+    /// assert_eq!(table, [("a", 3.0, 4.0), ("c", 2.0, 4.0), ("b", 1.0, 2.0)]);
+    /// assert!(result.to_json().contains(
+    ///     r#"{"name": "a", "results": [{"opponent": "c", "wins": 0, "draws": 2, "losses": 0, "points": 1}, "#
+    /// ));
+    /// # fs::remove_file(path)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn state_file(mut self, path: &str) -> Self {
+        self.state_file = Some(path.to_owned());
+        self
+    }
+
+    /// Plays every game of the tournament that hasn't been played yet, and returns the results.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StockfishError::InvalidTournamentState`] or [`StockfishError::InvalidPgn`]
+    /// if the state file can't be resumed from, or another [`StockfishError`] if an engine
+    /// couldn't be created, or if an error occurred while communicating with one. (Games
+    /// finished before an error are kept in the state file.)
+    pub fn run(&self) -> Result<TournamentResult> {
+        self.run_with(|_| {})
+    }
+
+    /// Plays every game of the tournament that hasn't been played yet, calling the given
+    /// function with the results so far after each game.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StockfishError::InvalidTournamentState`] or [`StockfishError::InvalidPgn`]
+    /// if the state file can't be resumed from, or another [`StockfishError`] if an engine
+    /// couldn't be created, or if an error occurred while communicating with one. (Games
+    /// finished before an error are kept in the state file.)
+    pub fn run_with<F: FnMut(&TournamentResult) + Send>(&self, on_game: F) -> Result<TournamentResult> {
+        let schedule = self.schedule();
+        let names = self.engines.iter().map(|engine| engine.name().to_owned()).collect();
+        let mut result = TournamentResult::new(names, self.format, schedule.len());
+
+        let mut state = None;
+        if let Some(path) = &self.state_file {
+            if Path::new(path).exists() {
+                for (round, record) in self.load_state(path, &schedule)? {
+                    result.record(round, record);
+                }
+            }
+            state = Some(OpenOptions::new().create(true).append(true).open(path)?);
+        }
+
+        let remaining: Vec<usize> = (0..schedule.len())
+            .filter(|round| !result.rounds.contains(round))
+            .collect();
+        if remaining.is_empty() {
+            return Ok(result);
+        }
+
+        // With as many engines of each configuration as there are games in progress, no
+        // game waits for an engine
+        let workers = self.concurrency.min(remaining.len());
+        let pools = self.engines.iter().enumerate()
+            .map(|(engine, config)| {
+                let plays = remaining.iter().any(|&round| schedule[round].white == engine || schedule[round].black == engine);
+                if plays {config.spawn_pool(workers).map(Some)} else {Ok(None)}
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let queue = Mutex::new(remaining.into_iter());
+        let progress = Mutex::new(Progress { result, on_game, state, error: None });
+        thread::scope(|scope| {
+            for _ in 0..workers {
+                scope.spawn(|| loop {
+                    if progress.lock().unwrap_or_else(PoisonError::into_inner).error.is_some() {
+                        break;
+                    }
+                    let next = queue.lock().unwrap_or_else(PoisonError::into_inner).next();
+                    let Some(round) = next else {
+                        break;
+                    };
+
+                    let record = self.play_game(&pools, round, &schedule[round]);
+                    let mut progress = progress.lock().unwrap_or_else(PoisonError::into_inner);
+                    if let Err(err) = record.and_then(|record| progress.record(round, record)) {
+                        progress.error.get_or_insert(err);
+                        break;
+                    }
+                });
+            }
+        });
+
+        let progress = progress.into_inner().unwrap_or_else(PoisonError::into_inner);
+        match progress.error {
+            Some(err) => Err(err),
+            None => Ok(progress.result),
+        }
+    }
+
+    /* Private Methods */
+
+    /// Lists the games of the tournament in the order in which they are played.
+    fn schedule(&self) -> Vec<ScheduledGame> {
+        let pairings: Vec<(usize, usize)> = match self.format {
+            TournamentFormat::RoundRobin => (0..self.engines.len())
+                .flat_map(|first| (first + 1..self.engines.len()).map(move |second| (first, second)))
+                .collect(),
+            TournamentFormat::Gauntlet => (1..self.engines.len()).map(|second| (0, second)).collect(),
+        };
+
+        let mut schedule = Vec::new();
+        for game in 0..self.games_per_pairing as usize {
+            let opening = if self.openings.is_empty() {0} else {game / 2 % self.openings.len()};
+            for &(first, second) in &pairings {
+                let (white, black) = if game.is_multiple_of(2) {(first, second)} else {(second, first)};
+                schedule.push(ScheduledGame { white, black, opening });
+            }
+        }
+        schedule
+    }
+
+    /// Reads the games saved in the state file, along with the index of their round.
+    fn load_state(&self, path: &str, schedule: &[ScheduledGame]) -> Result<Vec<(usize, GameRecord)>> {
+        let invalid = |reason: String| StockfishError::InvalidTournamentState { path: path.to_owned(), reason };
+
+        // If the tournament was interrupted while a game was being saved, that game is cut
+        // short; it is removed from the file, and its round is played again
+        let mut text = fs::read_to_string(path)?;
+        let last_game = last_game_start(&text);
+        if !is_complete_game(&text[last_game..]) {
+            text.truncate(last_game);
+            OpenOptions::new().write(true).open(path)?.set_len(last_game as u64)?;
+        }
+
+        let mut records: Vec<(usize, GameRecord)> = Vec::new();
+        for game in PgnReader::new(text.as_bytes()) {
+            let game = game?;
+            let round_tag = game.header("Round").unwrap_or("?");
+            let round = round_tag.parse::<usize>().ok()
+                .filter(|round| (1..=schedule.len()).contains(round))
+                .ok_or_else(|| invalid(format!("round {round_tag:?} isn't part of the tournament")))?;
+            if records.iter().any(|(index, _)| *index == round - 1) {
+                return Err(invalid(format!("round {round} was saved twice")));
+            }
+
+            let scheduled = &schedule[round - 1];
+            let (white, black) = (self.engines[scheduled.white].name(), self.engines[scheduled.black].name());
+            if game.header("White") != Some(white) || game.header("Black") != Some(black) {
+                return Err(invalid(format!(
+                    "round {round} was played by {} and {}, instead of {white} and {black}",
+                    game.header("White").unwrap_or("?"), game.header("Black").unwrap_or("?"),
+                )));
+            }
+
+            let result = match game.result() {
+                "1-0" => GameResult::WhiteWins,
+                "0-1" => GameResult::BlackWins,
+                "1/2-1/2" => GameResult::Draw,
+                other => return Err(invalid(format!("round {round} has no result ({other})"))),
+            };
+            let termination = game.header(TERMINATION_TAG)
+                .and_then(Termination::from_description)
+                .ok_or_else(|| invalid(format!("round {round} doesn't say how it ended")))?;
+
+            let record = GameRecord {
+                white: scheduled.white,
+                black: scheduled.black,
+                opening: scheduled.opening,
+                game,
+                result,
+                termination,
+            };
+            records.push((round - 1, record));
+        }
+        Ok(records)
+    }
+
+    fn play_game(&self, pools: &[Option<EnginePool>], round: usize, scheduled: &ScheduledGame) -> Result<GameRecord> {
+        let pool = |engine: usize| pools[engine].as_ref().expect("engines with games left should have a pool");
+        let mut white = pool(scheduled.white).checkout()?;
+        let mut black = pool(scheduled.black).checkout()?;
+
+        let starting_position = PgnGame::new();
+        let opening = self.openings.get(scheduled.opening).unwrap_or(&starting_position);
+        let (mut game, result, termination) =
+            engine_match::play_game(&mut white, &mut black, opening, self.time_control, self.adjudication)?;
+        game.set_header("Event", self.format.event());
+        game.set_header("Round", &(round + 1).to_string());
+        game.set_header("White", self.engines[scheduled.white].name());
+        game.set_header("Black", self.engines[scheduled.black].name());
+        game.set_header(TERMINATION_TAG, &termination.to_string());
+
+        Ok(GameRecord {
+            white: scheduled.white,
+            black: scheduled.black,
+            opening: scheduled.opening,
+            game,
+            result,
+            termination,
+        })
+    }
+}
+
+/// A game of a tournament's schedule, by the indices of its engines and opening.
+#[derive(Debug, Clone, Copy)]
+struct ScheduledGame {
+    white: usize,
+    black: usize,
+    opening: usize,
+}
+
+/// The progress of a tournament, shared between the threads that play its games.
+struct Progress<F> {
+    result: TournamentResult,
+    on_game: F,
+    state: Option<File>,
+    /// The first error that occurred, after which no more games are started.
+    error: Option<StockfishError>,
+}
+
+impl<F: FnMut(&TournamentResult)> Progress<F> {
+    fn record(&mut self, round: usize, record: GameRecord) -> Result<()> {
+        if let Some(state) = &mut self.state {
+            // A game that an interruption cuts short is dropped when the tournament is resumed
+            state.write_all(format!("{}\n", record.game.to_pgn()).as_bytes())?;
+            state.flush()?;
+        }
+        self.result.record(round, record);
+        (self.on_game)(&self.result);
+        Ok(())
+    }
+}
+
+/// The results of a [`Tournament`] so far.
+///
+/// Its [`fmt::Display`] implementation lists the standings, followed by the crosstable.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TournamentResult {
+    names: Vec<String>,
+    format: TournamentFormat,
+    scheduled_games: usize,
+    /// The index of the round of each game.
+    rounds: Vec<usize>,
+    games: Vec<GameRecord>,
+}
+
+impl TournamentResult {
+    /// Returns the names of the engines, in the order in which they were given to the tournament.
+    #[must_use]
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    #[must_use]
+    pub fn format(&self) -> TournamentFormat {
+        self.format
+    }
+
+    /// Returns the games played so far, in the order of their rounds. The indices of
+    /// their engines are those of [`TournamentResult::names`].
+    #[must_use]
+    pub fn games(&self) -> &[GameRecord] {
+        &self.games
+    }
+
+    /// Returns the total number of games of the tournament, played or not.
+    #[must_use]
+    pub fn scheduled_games(&self) -> usize {
+        self.scheduled_games
+    }
+
+    #[must_use]
+    pub fn is_complete(&self) -> bool {
+        self.games.len() == self.scheduled_games
+    }
+
+    /// Returns the numbers of games that an engine won, drew and lost against an opponent.
+    #[must_use]
+    pub fn head_to_head(&self, engine: usize, opponent: usize) -> (u32, u32, u32) {
+        let (mut wins, mut draws, mut losses) = (0, 0, 0);
+        for game in &self.games {
+            let plays = (game.white == engine && game.black == opponent) || (game.white == opponent && game.black == engine);
+            if !plays {
+                continue;
+            }
+            match (game.result, game.white == engine) {
+                (GameResult::Draw, _) => draws += 1,
+                (GameResult::WhiteWins, true) | (GameResult::BlackWins, false) => wins += 1,
+                _ => losses += 1,
+            }
+        }
+        (wins, draws, losses)
+    }
+
+    /// Returns the standings: every engine ranked by points, with ties broken by the
+    /// Sonneborn-Berger score.
+    #[must_use]
+    pub fn standings(&self) -> Vec<Standing> {
+        let mut standings: Vec<Standing> = (0..self.names.len())
+            .map(|engine| {
+                let (mut wins, mut draws, mut losses) = (0, 0, 0);
+                for opponent in 0..self.names.len() {
+                    let (opponent_wins, opponent_draws, opponent_losses) = self.head_to_head(engine, opponent);
+                    wins += opponent_wins;
+                    draws += opponent_draws;
+                    losses += opponent_losses;
+                }
+                Standing {
+                    engine,
+                    name: self.names[engine].clone(),
+                    wins,
+                    draws,
+                    losses,
+                    sonneborn_berger: 0.0,
+                }
+            })
+            .collect();
+
+        let points: Vec<f64> = standings.iter().map(Standing::points).collect();
+        for game in &self.games {
+            standings[game.white].sonneborn_berger += game.result.score(Color::White) * points[game.black];
+            standings[game.black].sonneborn_berger += game.result.score(Color::Black) * points[game.white];
+        }
+
+        standings.sort_by(|a, b| {
+            b.points().total_cmp(&a.points())
+                .then(b.sonneborn_berger.total_cmp(&a.sonneborn_berger))
+                .then(a.engine.cmp(&b.engine))
+        });
+        standings
+    }
+
+    /// Formats the results as JSON: the standings (in order of rank), and the crosstable
+    /// (the results of each engine against each opponent that it played.) Infinite Elo
+    /// estimates are given as `null`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use stockfish::{EngineConfig, Tournament, TournamentFormat};
+    /// # fn main() -> Result<(), stockfish::StockfishError> {
+    /// let engines = vec![
+    ///     EngineConfig::new("base", "stockfish.exe"),
+    ///     EngineConfig::new("hash-16", "stockfish.exe").option("Hash", "16"),
+    ///     EngineConfig::new("threads-2", "stockfish.exe").option("Threads", "2"),
+    /// ];
+    /// let result = Tournament::new(engines, TournamentFormat::Gauntlet).run()?;
+    /// // {
+    /// //   "format": "gauntlet",
+    /// //   "games_played": 4,
+    /// //   "scheduled_games": 4,
+    /// //   "standings": [
+    /// //     {"rank": 1, "name": "base", "games": 4, "points": 2.5, "wins": 1, ...},
+    /// //     ...
+    /// //   ],
+    /// //   "crosstable": [
+    /// //     {"name": "base", "results": [{"opponent": "hash-16", "wins": 1, ...}, ...]},
+    /// //     ...
+    /// //   ]
+    /// // }
+    /// println!("{}", result.to_json());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        let standings = self.standings();
+        let mut json = String::from("{\n");
+        json.push_str(&format!("  \"format\": {},\n", json_string(&self.format.to_string())));
+        json.push_str(&format!("  \"games_played\": {},\n", self.games.len()));
+        json.push_str(&format!("  \"scheduled_games\": {},\n", self.scheduled_games));
+
+        let entries: Vec<String> = standings.iter().enumerate()
+            .map(|(rank, standing)| format!(
+                "    {{\"rank\": {}, \"name\": {}, \"games\": {}, \"points\": {}, \"wins\": {}, \"draws\": {}, \
+                \"losses\": {}, \"score\": {}, \"elo\": {}, \"elo_error\": {}, \"sonneborn_berger\": {}}}",
+                rank + 1, json_string(&standing.name), standing.games(), json_number(standing.points()),
+                standing.wins, standing.draws, standing.losses, json_number(standing.score()),
+                json_number(standing.elo_difference()), json_number(standing.elo_error()),
+                json_number(standing.sonneborn_berger),
+            ))
+            .collect();
+        json.push_str(&format!("  \"standings\": [\n{}\n  ],\n", entries.join(",\n")));
+
+        let rows: Vec<String> = standings.iter()
+            .map(|standing| {
+                let results: Vec<String> = standings.iter()
+                    .filter_map(|opponent| {
+                        let (wins, draws, losses) = self.head_to_head(standing.engine, opponent.engine);
+                        (wins + draws + losses > 0).then(|| format!(
+                            "{{\"opponent\": {}, \"wins\": {wins}, \"draws\": {draws}, \"losses\": {losses}, \"points\": {}}}",
+                            json_string(&opponent.name), json_number(f64::from(wins) + f64::from(draws) / 2.0),
+                        ))
+                    })
+                    .collect();
+                format!("    {{\"name\": {}, \"results\": [{}]}}", json_string(&standing.name), results.join(", "))
+            })
+            .collect();
+        json.push_str(&format!("  \"crosstable\": [\n{}\n  ]\n", rows.join(",\n")));
+        json.push('}');
+        json
+    }
+
+    /* Private Methods */
+
+    fn new(names: Vec<String>, format: TournamentFormat, scheduled_games: usize) -> TournamentResult {
+        TournamentResult { names, format, scheduled_games, rounds: Vec::new(), games: Vec::new() }
+    }
+
+    fn record(&mut self, round: usize, record: GameRecord) {
+        let index = self.rounds.partition_point(|&other| other < round);
+        self.rounds.insert(index, round);
+        self.games.insert(index, record);
+    }
+}
+
+impl fmt::Display for TournamentResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let standings = self.standings();
+        let name_width = self.names.iter().map(|name| name.chars().count()).max().unwrap_or(0).max(4);
+
+        writeln!(f, "{}, {} of {} games played", self.format.event(), self.games.len(), self.scheduled_games)?;
+        writeln!(f)?;
+        writeln!(
+            f,
+            "Rank  {:name_width$}  Games  Points    W    D    L   Score      Elo      ±      SB",
+            "Name",
+        )?;
+        for (rank, standing) in standings.iter().enumerate() {
+            writeln!(
+                f,
+                "{:>4}  {:name_width$}  {:>5}  {:>6.1}  {:>3}  {:>3}  {:>3}  {:>5.1}%  {:>+7.1}  {:>5.1}  {:>6.2}",
+                rank + 1, standing.name, standing.games(), standing.points(),
+                standing.wins, standing.draws, standing.losses, standing.score() * 100.0,
+                standing.elo_difference(), standing.elo_error(), standing.sonneborn_berger,
+            )?;
+        }
+        writeln!(f)?;
+
+        // The crosstable, with the engines in order of rank along both axes
+        write!(f, "      {:name_width$}", "")?;
+        for rank in 1..=standings.len() {
+            write!(f, "  {rank:>7}")?;
+        }
+        writeln!(f)?;
+        for (rank, standing) in standings.iter().enumerate() {
+            write!(f, "{:>4}  {:name_width$}", rank + 1, standing.name)?;
+            for opponent in &standings {
+                let (wins, draws, losses) = self.head_to_head(standing.engine, opponent.engine);
+                let cell = if opponent.engine == standing.engine {
+                    "-".to_owned()
+                } else if wins + draws + losses == 0 {
+                    String::new()
+                } else {
+                    format!("{}/{}", f64::from(wins) + f64::from(draws) / 2.0, wins + draws + losses)
+                };
+                write!(f, "  {cell:>7}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// The results of one engine of a [`Tournament`], as ranked by [`TournamentResult::standings`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Standing {
+    engine: usize,
+    name: String,
+    wins: u32,
+    draws: u32,
+    losses: u32,
+    sonneborn_berger: f64,
+}
+
+impl Standing {
+    /// Returns the index of the engine, as in [`TournamentResult::names`].
+    #[must_use]
+    pub fn engine(&self) -> usize {
+        self.engine
+    }
+
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[must_use]
+    pub fn games(&self) -> u32 {
+        self.wins + self.draws + self.losses
+    }
+
+    #[must_use]
+    pub fn wins(&self) -> u32 {
+        self.wins
+    }
+
+    #[must_use]
+    pub fn draws(&self) -> u32 {
+        self.draws
+    }
+
+    #[must_use]
+    pub fn losses(&self) -> u32 {
+        self.losses
+    }
+
+    /// Returns the points scored: 1 per win and 0.5 per draw.
+    #[must_use]
+    pub fn points(&self) -> f64 {
+        f64::from(self.wins) + f64::from(self.draws) / 2.0
+    }
+
+    /// Returns the points scored as a fraction of the games played (0.5 if no game has been played.)
+    #[must_use]
+    pub fn score(&self) -> f64 {
+        if self.games() == 0 {
+            return 0.5;
+        }
+        self.points() / f64::from(self.games())
+    }
+
+    /// Returns the estimated Elo difference of the engine over the average of its opponents
+    /// (infinite if it has scored every point, or none.)
+    #[must_use]
+    pub fn elo_difference(&self) -> f64 {
+        elo::elo_from_score(self.score())
+    }
+
+    /// Returns the half-width of the 95% confidence interval of the Elo difference.
+    #[must_use]
+    pub fn elo_error(&self) -> f64 {
+        elo::elo_error(self.wins, self.draws, self.losses)
+    }
+
+    /// Returns the Sonneborn-Berger score: the sum of the final points of the opponents
+    /// that the engine beat, plus half of those of the opponents that it drew against.
+    #[must_use]
+    pub fn sonneborn_berger(&self) -> f64 {
+        self.sonneborn_berger
+    }
+}
+
+/// Returns the offset at which the last game of a PGN begins: that of the last header
+/// section that follows movetext.
+fn last_game_start(pgn: &str) -> usize {
+    let (mut start, mut offset) = (0, 0);
+    let (mut in_movetext, mut in_comment) = (false, false);
+    for line in pgn.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        if !in_comment && trimmed.starts_with('[') {
+            if in_movetext {
+                start = offset;
+                in_movetext = false;
+            }
+        } else if !in_comment && !trimmed.is_empty() && !trimmed.starts_with('%') {
+            in_movetext = true;
+        }
+        for char in line.chars() {
+            match char {
+                '{' => in_comment = true,
+                '}' => in_comment = false,
+                ';' if !in_comment => break,
+                _ => {}
+            }
+        }
+        offset += line.len();
+    }
+    start
+}
+
+/// Returns `true` if the text of a saved game ends with its result, as it does once the
+/// game has been written in full.
+fn is_complete_game(text: &str) -> bool {
+    let last_token = text.split_whitespace().next_back();
+    last_token.is_some_and(|token| ["1-0", "0-1", "1/2-1/2", "*"].contains(&token)) && PgnGame::parse(text).is_ok()
+}
+
+/// Formats a string as a JSON string literal.
+fn json_string(value: &str) -> String {
+    let mut json = String::from("\"");
+    for char in value.chars() {
+        match char {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            char if char.is_control() => json.push_str(&format!("\\u{:04x}", u32::from(char))),
+            char => json.push(char),
+        }
+    }
+    json.push('"');
+    json
+}
+
+/// Formats a number as JSON, which has no representation for infinities or NaN.
+fn json_number(value: f64) -> String {
+    if value.is_finite() {format!("{value}")} else {"null".to_owned()}
+}